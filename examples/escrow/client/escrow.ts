@@ -0,0 +1,280 @@
+// Generated by Poseidon from the `EscrowProgram` program.
+
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+
+export const PROGRAM_ID = new PublicKey("11111111111111111111111111111111");
+const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const SYSTEM_PROGRAM_ID = new PublicKey("11111111111111111111111111111111");
+
+class Writer {
+  private buf: number[] = [];
+
+  private int(value: bigint, bytes: number): this {
+    let v = BigInt.asUintN(bytes * 8, value);
+    for (let i = 0; i < bytes; i++) {
+      this.buf.push(Number(v & 0xffn));
+      v >>= 8n;
+    }
+    return this;
+  }
+
+  u8(v: number) { return this.int(BigInt(v), 1); }
+  i8(v: number) { return this.int(BigInt(v), 1); }
+  u16(v: number) { return this.int(BigInt(v), 2); }
+  i16(v: number) { return this.int(BigInt(v), 2); }
+  u32(v: number) { return this.int(BigInt(v), 4); }
+  i32(v: number) { return this.int(BigInt(v), 4); }
+  u64(v: bigint) { return this.int(v, 8); }
+  i64(v: bigint) { return this.int(v, 8); }
+  u128(v: bigint) { return this.int(v, 16); }
+  i128(v: bigint) { return this.int(v, 16); }
+  bool(v: boolean) { return this.u8(v ? 1 : 0); }
+
+  raw(v: Uint8Array): this {
+    this.buf.push(...v);
+    return this;
+  }
+
+  bytes(v: Uint8Array) { return this.u32(v.length).raw(v); }
+  string(v: string) { return this.bytes(new TextEncoder().encode(v)); }
+  pubkey(v: PublicKey) { return this.raw(v.toBytes()); }
+
+  vec<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    return this.u32(items.length).array(items, item);
+  }
+
+  array<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    items.forEach((v) => item(this, v));
+    return this;
+  }
+
+  option<T>(value: T | null | undefined, item: (w: Writer, v: T) => void): this {
+    if (value === null || value === undefined) {
+      return this.u8(0);
+    }
+    item(this.u8(1), value);
+    return this;
+  }
+
+  toBuffer(): Buffer {
+    return Buffer.from(this.buf);
+  }
+}
+
+class Reader {
+  private offset = 0;
+
+  constructor(private data: Uint8Array) {}
+
+  private int(bytes: number, signed: boolean): bigint {
+    let v = 0n;
+    for (let i = bytes - 1; i >= 0; i--) {
+      v = (v << 8n) | BigInt(this.data[this.offset + i]);
+    }
+    this.offset += bytes;
+    return signed ? BigInt.asIntN(bytes * 8, v) : v;
+  }
+
+  u8() { return Number(this.int(1, false)); }
+  i8() { return Number(this.int(1, true)); }
+  u16() { return Number(this.int(2, false)); }
+  i16() { return Number(this.int(2, true)); }
+  u32() { return Number(this.int(4, false)); }
+  i32() { return Number(this.int(4, true)); }
+  u64() { return this.int(8, false); }
+  i64() { return this.int(8, true); }
+  u128() { return this.int(16, false); }
+  i128() { return this.int(16, true); }
+  bool() { return this.u8() !== 0; }
+
+  raw(length: number): Uint8Array {
+    const v = this.data.slice(this.offset, this.offset + length);
+    this.offset += length;
+    return v;
+  }
+
+  bytes() { return this.raw(this.u32()); }
+  string() { return new TextDecoder().decode(this.bytes()); }
+  pubkey() { return new PublicKey(this.raw(32)); }
+
+  vec<T>(item: (r: Reader) => T): T[] {
+    return this.array(this.u32(), item);
+  }
+
+  array<T>(length: number, item: (r: Reader) => T): T[] {
+    return Array.from({ length }, () => item(this));
+  }
+
+  option<T>(item: (r: Reader) => T): T | null {
+    return this.u8() === 0 ? null : item(this);
+  }
+}
+
+function checkDiscriminator(data: Uint8Array, discriminator: Uint8Array, name: string) {
+  if (!discriminator.every((b, i) => data[i] === b)) {
+    throw new Error(`Account is not a ${name}`);
+  }
+}
+
+export interface EscrowState {
+  maker: PublicKey;
+  makerMint: PublicKey;
+  takerMint: PublicKey;
+  amount: bigint;
+  seed: bigint;
+  authBump: number;
+  escrowBump: number;
+  vaultBump: number;
+}
+
+export const ESCROW_STATE_DISCRIMINATOR = new Uint8Array([19, 90, 148, 111, 55, 130, 229, 108]);
+
+export function decodeEscrowState(data: Uint8Array): EscrowState {
+  checkDiscriminator(data, ESCROW_STATE_DISCRIMINATOR, "EscrowState");
+  const r = new Reader(data.subarray(8));
+  return {
+    maker: r.pubkey(),
+    makerMint: r.pubkey(),
+    takerMint: r.pubkey(),
+    amount: r.u64(),
+    seed: r.u64(),
+    authBump: r.u8(),
+    escrowBump: r.u8(),
+    vaultBump: r.u8(),
+  };
+}
+
+export function findEscrowAddress(maker: PublicKey, seed: bigint, programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("escrow"), maker.toBuffer(), new Writer().u64(seed).toBuffer()], programId);
+}
+
+export function findAuthAddress(programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("auth")], programId);
+}
+
+export function findVaultAddress(escrow: PublicKey, programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("vault"), escrow.toBuffer()], programId);
+}
+
+export interface MakeAccounts {
+  maker: PublicKey;
+  /** `findEscrowAddress(maker, seed)[0]` */
+  escrow: PublicKey;
+  makerAta: PublicKey;
+  makerMint: PublicKey;
+  takerMint: PublicKey;
+  /** `findAuthAddress()[0]` */
+  auth: PublicKey;
+  /** `findVaultAddress(escrow)[0]` */
+  vault: PublicKey;
+}
+
+export interface MakeArgs {
+  depositAmount: bigint;
+  offerAmount: bigint;
+  seed: bigint;
+}
+
+export function make(
+  accounts: MakeAccounts,
+  args: MakeArgs,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([138, 227, 232, 77, 223, 166, 96, 197]));
+  w.u64(args.depositAmount);
+  w.u64(args.offerAmount);
+  w.u64(args.seed);
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.maker, isSigner: true, isWritable: true },
+      { pubkey: accounts.escrow, isSigner: false, isWritable: true },
+      { pubkey: accounts.makerAta, isSigner: false, isWritable: true },
+      { pubkey: accounts.makerMint, isSigner: false, isWritable: true },
+      { pubkey: accounts.takerMint, isSigner: false, isWritable: true },
+      { pubkey: accounts.auth, isSigner: false, isWritable: false },
+      { pubkey: accounts.vault, isSigner: false, isWritable: true },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
+
+export interface RefundAccounts {
+  maker: PublicKey;
+  makerAta: PublicKey;
+  makerMint: PublicKey;
+  /** `findAuthAddress()[0]` */
+  auth: PublicKey;
+  /** `findVaultAddress(escrow)[0]` */
+  vault: PublicKey;
+  /** `findEscrowAddress(maker, seed)[0]` */
+  escrow: PublicKey;
+}
+
+export function refund(
+  accounts: RefundAccounts,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([2, 96, 183, 251, 63, 208, 46, 46]));
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.maker, isSigner: true, isWritable: true },
+      { pubkey: accounts.makerAta, isSigner: false, isWritable: true },
+      { pubkey: accounts.makerMint, isSigner: false, isWritable: true },
+      { pubkey: accounts.auth, isSigner: false, isWritable: false },
+      { pubkey: accounts.vault, isSigner: false, isWritable: true },
+      { pubkey: accounts.escrow, isSigner: false, isWritable: true },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
+
+export interface TakeAccounts {
+  taker: PublicKey;
+  maker: PublicKey;
+  makerAta: PublicKey;
+  takerAta: PublicKey;
+  takerReceiveAta: PublicKey;
+  makerMint: PublicKey;
+  takerMint: PublicKey;
+  /** `findAuthAddress()[0]` */
+  auth: PublicKey;
+  /** `findVaultAddress(escrow)[0]` */
+  vault: PublicKey;
+  /** `findEscrowAddress(maker, seed)[0]` */
+  escrow: PublicKey;
+}
+
+export function take(
+  accounts: TakeAccounts,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([149, 226, 52, 104, 6, 142, 230, 39]));
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.taker, isSigner: true, isWritable: true },
+      { pubkey: accounts.maker, isSigner: false, isWritable: true },
+      { pubkey: accounts.makerAta, isSigner: false, isWritable: true },
+      { pubkey: accounts.takerAta, isSigner: false, isWritable: true },
+      { pubkey: accounts.takerReceiveAta, isSigner: false, isWritable: true },
+      { pubkey: accounts.makerMint, isSigner: false, isWritable: true },
+      { pubkey: accounts.takerMint, isSigner: false, isWritable: true },
+      { pubkey: accounts.auth, isSigner: false, isWritable: false },
+      { pubkey: accounts.vault, isSigner: false, isWritable: true },
+      { pubkey: accounts.escrow, isSigner: false, isWritable: true },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Transfer as TransferSPL, TokenAccount, Mint, transfer as transfer_spl, Token},
+    token::{Mint, Token, TokenAccount, Transfer as TransferSPL, transfer as transfer_spl},
 };
 declare_id!("11111111111111111111111111111111");
 #[program]
@@ -39,12 +39,13 @@ pub mod escrow_program {
             to: ctx.accounts.maker_ata.to_account_info(),
             authority: ctx.accounts.auth.to_account_info(),
         };
-        let signer_seeds = &[&b"auth"[..], &[ctx.accounts.escrow.auth_bump]];
-        let binding = [&signer_seeds[..]];
+        let signer_seeds: &[&[&[u8]]; 1] = &[
+            &[b"auth", &[ctx.accounts.escrow.auth_bump]],
+        ];
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             cpi_accounts,
-            &binding,
+            signer_seeds,
         );
         transfer_spl(cpi_ctx, ctx.accounts.escrow.amount)?;
         Ok(())
@@ -65,151 +66,131 @@ pub mod escrow_program {
             to: ctx.accounts.taker_receive_ata.to_account_info(),
             authority: ctx.accounts.auth.to_account_info(),
         };
-        let signer_seeds = &[&b"auth"[..], &[ctx.accounts.escrow.auth_bump]];
-        let binding = [&signer_seeds[..]];
+        let signer_seeds: &[&[&[u8]]; 1] = &[
+            &[b"auth", &[ctx.accounts.escrow.auth_bump]],
+        ];
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             cpi_accounts,
-            &binding,
+            signer_seeds,
         );
         transfer_spl(cpi_ctx, ctx.accounts.escrow.amount)?;
         Ok(())
     }
 }
 #[derive(Accounts)]
-#[instruction(seed:u64)]
+#[instruction(deposit_amount: u64, offer_amount: u64, seed: u64)]
 pub struct MakeContext<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
     #[account(
-        mut,
-        associated_token::mint = maker_mint,
-        associated_token::authority = maker,
+        init,
+        payer = maker,
+        space = 123,
+        seeds = [b"escrow", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
     )]
+    pub escrow: Account<'info, EscrowState>,
+    #[account(mut, associated_token::mint = maker_mint, associated_token::authority = maker)]
     pub maker_ata: Account<'info, TokenAccount>,
-    #[account()]
+    #[account(mut)]
     pub maker_mint: Account<'info, Mint>,
-    #[account()]
+    #[account(mut)]
     pub taker_mint: Account<'info, Mint>,
     #[account(seeds = [b"auth"], bump)]
     /// CHECK: This acc is safe
     pub auth: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub maker: Signer<'info>,
     #[account(
         init,
         payer = maker,
-        seeds = [b"vault",
-        escrow.key().as_ref()],
+        seeds = [b"vault", escrow.key().as_ref()],
         token::mint = maker_mint,
         token::authority = auth,
-        bump,
+        bump
     )]
     pub vault: Account<'info, TokenAccount>,
-    #[account(
-        init,
-        payer = maker,
-        space = 123,
-        seeds = [b"escrow",
-        maker.key().as_ref(),
-        seed.to_le_bytes().as_ref()],
-        bump,
-    )]
-    pub escrow: Account<'info, EscrowState>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 pub struct RefundContext<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(mut, associated_token::mint = maker_mint, associated_token::authority = maker)]
+    pub maker_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub maker_mint: Account<'info, Mint>,
+    #[account(seeds = [b"auth"], bump)]
+    /// CHECK: This acc is safe
+    pub auth: UncheckedAccount<'info>,
     #[account(
         mut,
-        seeds = [b"vault",
-        escrow.key().as_ref()],
+        seeds = [b"vault", escrow.key().as_ref()],
         token::mint = maker_mint,
         token::authority = auth,
-        bump,
+        bump
     )]
     pub vault: Account<'info, TokenAccount>,
-    #[account(seeds = [b"auth"], bump)]
-    /// CHECK: This acc is safe
-    pub auth: UncheckedAccount<'info>,
     #[account(
         mut,
-        seeds = [b"escrow",
-        maker.key().as_ref(),
-        escrow.seed.to_le_bytes().as_ref()],
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         has_one = maker,
         bump,
-        close = maker,
+        close = maker
     )]
     pub escrow: Account<'info, EscrowState>,
-    #[account(mut)]
-    pub maker: Signer<'info>,
-    #[account(
-        mut,
-        associated_token::mint = maker_mint,
-        associated_token::authority = maker,
-    )]
-    pub maker_ata: Account<'info, TokenAccount>,
-    #[account()]
-    pub maker_mint: Account<'info, Mint>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 pub struct TakeContext<'info> {
-    #[account()]
-    pub maker_mint: Account<'info, Mint>,
-    #[account(
-        init_if_needed,
-        payer = taker,
-        associated_token::mint = maker_mint,
-        associated_token::authority = taker,
-    )]
-    pub taker_receive_ata: Account<'info, TokenAccount>,
     #[account(mut)]
     pub taker: Signer<'info>,
-    #[account()]
-    pub taker_mint: Account<'info, Mint>,
     #[account(mut)]
     pub maker: SystemAccount<'info>,
+    #[account(mut, associated_token::mint = maker_mint, associated_token::authority = maker)]
+    pub maker_ata: Account<'info, TokenAccount>,
     #[account(
         init_if_needed,
         payer = taker,
         associated_token::mint = maker_mint,
-        associated_token::authority = taker,
+        associated_token::authority = taker
     )]
     pub taker_ata: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = taker
+    )]
+    pub taker_receive_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub maker_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub taker_mint: Account<'info, Mint>,
+    #[account(seeds = [b"auth"], bump)]
+    /// CHECK: This acc is safe
+    pub auth: UncheckedAccount<'info>,
     #[account(
         mut,
-        seeds = [b"vault",
-        escrow.key().as_ref()],
+        seeds = [b"vault", escrow.key().as_ref()],
         token::mint = maker_mint,
         token::authority = auth,
-        bump,
+        bump
     )]
     pub vault: Account<'info, TokenAccount>,
     #[account(
         mut,
-        seeds = [b"escrow",
-        maker.key().as_ref(),
-        escrow.seed.to_le_bytes().as_ref()],
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         has_one = maker,
         has_one = maker_mint,
         has_one = taker_mint,
         bump,
-        close = maker,
+        close = maker
     )]
     pub escrow: Account<'info, EscrowState>,
-    #[account(
-        mut,
-        associated_token::mint = maker_mint,
-        associated_token::authority = maker,
-    )]
-    pub maker_ata: Account<'info, TokenAccount>,
-    #[account(seeds = [b"auth"], bump)]
-    /// CHECK: This acc is safe
-    pub auth: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -0,0 +1,170 @@
+// Generated by Poseidon from the `FavoritesProgram` program.
+
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+
+export const PROGRAM_ID = new PublicKey("11111111111111111111111111111111");
+const SYSTEM_PROGRAM_ID = new PublicKey("11111111111111111111111111111111");
+
+class Writer {
+  private buf: number[] = [];
+
+  private int(value: bigint, bytes: number): this {
+    let v = BigInt.asUintN(bytes * 8, value);
+    for (let i = 0; i < bytes; i++) {
+      this.buf.push(Number(v & 0xffn));
+      v >>= 8n;
+    }
+    return this;
+  }
+
+  u8(v: number) { return this.int(BigInt(v), 1); }
+  i8(v: number) { return this.int(BigInt(v), 1); }
+  u16(v: number) { return this.int(BigInt(v), 2); }
+  i16(v: number) { return this.int(BigInt(v), 2); }
+  u32(v: number) { return this.int(BigInt(v), 4); }
+  i32(v: number) { return this.int(BigInt(v), 4); }
+  u64(v: bigint) { return this.int(v, 8); }
+  i64(v: bigint) { return this.int(v, 8); }
+  u128(v: bigint) { return this.int(v, 16); }
+  i128(v: bigint) { return this.int(v, 16); }
+  bool(v: boolean) { return this.u8(v ? 1 : 0); }
+
+  raw(v: Uint8Array): this {
+    this.buf.push(...v);
+    return this;
+  }
+
+  bytes(v: Uint8Array) { return this.u32(v.length).raw(v); }
+  string(v: string) { return this.bytes(new TextEncoder().encode(v)); }
+  pubkey(v: PublicKey) { return this.raw(v.toBytes()); }
+
+  vec<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    return this.u32(items.length).array(items, item);
+  }
+
+  array<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    items.forEach((v) => item(this, v));
+    return this;
+  }
+
+  option<T>(value: T | null | undefined, item: (w: Writer, v: T) => void): this {
+    if (value === null || value === undefined) {
+      return this.u8(0);
+    }
+    item(this.u8(1), value);
+    return this;
+  }
+
+  toBuffer(): Buffer {
+    return Buffer.from(this.buf);
+  }
+}
+
+class Reader {
+  private offset = 0;
+
+  constructor(private data: Uint8Array) {}
+
+  private int(bytes: number, signed: boolean): bigint {
+    let v = 0n;
+    for (let i = bytes - 1; i >= 0; i--) {
+      v = (v << 8n) | BigInt(this.data[this.offset + i]);
+    }
+    this.offset += bytes;
+    return signed ? BigInt.asIntN(bytes * 8, v) : v;
+  }
+
+  u8() { return Number(this.int(1, false)); }
+  i8() { return Number(this.int(1, true)); }
+  u16() { return Number(this.int(2, false)); }
+  i16() { return Number(this.int(2, true)); }
+  u32() { return Number(this.int(4, false)); }
+  i32() { return Number(this.int(4, true)); }
+  u64() { return this.int(8, false); }
+  i64() { return this.int(8, true); }
+  u128() { return this.int(16, false); }
+  i128() { return this.int(16, true); }
+  bool() { return this.u8() !== 0; }
+
+  raw(length: number): Uint8Array {
+    const v = this.data.slice(this.offset, this.offset + length);
+    this.offset += length;
+    return v;
+  }
+
+  bytes() { return this.raw(this.u32()); }
+  string() { return new TextDecoder().decode(this.bytes()); }
+  pubkey() { return new PublicKey(this.raw(32)); }
+
+  vec<T>(item: (r: Reader) => T): T[] {
+    return this.array(this.u32(), item);
+  }
+
+  array<T>(length: number, item: (r: Reader) => T): T[] {
+    return Array.from({ length }, () => item(this));
+  }
+
+  option<T>(item: (r: Reader) => T): T | null {
+    return this.u8() === 0 ? null : item(this);
+  }
+}
+
+function checkDiscriminator(data: Uint8Array, discriminator: Uint8Array, name: string) {
+  if (!discriminator.every((b, i) => data[i] === b)) {
+    throw new Error(`Account is not a ${name}`);
+  }
+}
+
+export interface Favorites {
+  number: bigint;
+  color: string;
+  hobbies: string[];
+}
+
+export const FAVORITES_DISCRIMINATOR = new Uint8Array([44, 205, 48, 25, 172, 96, 48, 27]);
+
+export function decodeFavorites(data: Uint8Array): Favorites {
+  checkDiscriminator(data, FAVORITES_DISCRIMINATOR, "Favorites");
+  const r = new Reader(data.subarray(8));
+  return {
+    number: r.u64(),
+    color: r.string(),
+    hobbies: r.vec((r) => r.string()),
+  };
+}
+
+export function findFavoritesAddress(owner: PublicKey, programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("favorites"), owner.toBuffer()], programId);
+}
+
+export interface SetFavoritesAccounts {
+  owner: PublicKey;
+  /** `findFavoritesAddress(owner)[0]` */
+  favorites: PublicKey;
+}
+
+export interface SetFavoritesArgs {
+  number: bigint;
+  color: string;
+  hobbies: string[];
+}
+
+export function setFavorites(
+  accounts: SetFavoritesAccounts,
+  args: SetFavoritesArgs,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([211, 137, 87, 135, 161, 224, 187, 120]));
+  w.u64(args.number);
+  w.string(args.color);
+  w.vec(args.hobbies, (w, v) => w.string(v));
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.favorites, isSigner: false, isWritable: true },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
@@ -10,24 +10,23 @@ pub mod favorites_program {
         hobbies: Vec<String>,
     ) -> Result<()> {
         ctx.accounts.favorites.number = number;
-        ctx.accounts.favorites.color = color;
-        ctx.accounts.favorites.hobbies = hobbies;
+        ctx.accounts.favorites.color = color.clone();
+        ctx.accounts.favorites.hobbies = hobbies.clone();
         Ok(())
     }
 }
 #[derive(Accounts)]
 pub struct SetFavoritesContext<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
     #[account(
         init_if_needed,
         payer = owner,
         space = 344,
-        seeds = [b"favorites",
-        owner.key().as_ref()],
-        bump,
+        seeds = [b"favorites", owner.key().as_ref()],
+        bump
     )]
     pub favorites: Account<'info, Favorites>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 #[account]
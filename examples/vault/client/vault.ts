@@ -0,0 +1,244 @@
+// Generated by Poseidon from the `VaultProgram` program.
+
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+
+export const PROGRAM_ID = new PublicKey("11111111111111111111111111111111");
+const SYSTEM_PROGRAM_ID = new PublicKey("11111111111111111111111111111111");
+
+class Writer {
+  private buf: number[] = [];
+
+  private int(value: bigint, bytes: number): this {
+    let v = BigInt.asUintN(bytes * 8, value);
+    for (let i = 0; i < bytes; i++) {
+      this.buf.push(Number(v & 0xffn));
+      v >>= 8n;
+    }
+    return this;
+  }
+
+  u8(v: number) { return this.int(BigInt(v), 1); }
+  i8(v: number) { return this.int(BigInt(v), 1); }
+  u16(v: number) { return this.int(BigInt(v), 2); }
+  i16(v: number) { return this.int(BigInt(v), 2); }
+  u32(v: number) { return this.int(BigInt(v), 4); }
+  i32(v: number) { return this.int(BigInt(v), 4); }
+  u64(v: bigint) { return this.int(v, 8); }
+  i64(v: bigint) { return this.int(v, 8); }
+  u128(v: bigint) { return this.int(v, 16); }
+  i128(v: bigint) { return this.int(v, 16); }
+  bool(v: boolean) { return this.u8(v ? 1 : 0); }
+
+  raw(v: Uint8Array): this {
+    this.buf.push(...v);
+    return this;
+  }
+
+  bytes(v: Uint8Array) { return this.u32(v.length).raw(v); }
+  string(v: string) { return this.bytes(new TextEncoder().encode(v)); }
+  pubkey(v: PublicKey) { return this.raw(v.toBytes()); }
+
+  vec<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    return this.u32(items.length).array(items, item);
+  }
+
+  array<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    items.forEach((v) => item(this, v));
+    return this;
+  }
+
+  option<T>(value: T | null | undefined, item: (w: Writer, v: T) => void): this {
+    if (value === null || value === undefined) {
+      return this.u8(0);
+    }
+    item(this.u8(1), value);
+    return this;
+  }
+
+  toBuffer(): Buffer {
+    return Buffer.from(this.buf);
+  }
+}
+
+class Reader {
+  private offset = 0;
+
+  constructor(private data: Uint8Array) {}
+
+  private int(bytes: number, signed: boolean): bigint {
+    let v = 0n;
+    for (let i = bytes - 1; i >= 0; i--) {
+      v = (v << 8n) | BigInt(this.data[this.offset + i]);
+    }
+    this.offset += bytes;
+    return signed ? BigInt.asIntN(bytes * 8, v) : v;
+  }
+
+  u8() { return Number(this.int(1, false)); }
+  i8() { return Number(this.int(1, true)); }
+  u16() { return Number(this.int(2, false)); }
+  i16() { return Number(this.int(2, true)); }
+  u32() { return Number(this.int(4, false)); }
+  i32() { return Number(this.int(4, true)); }
+  u64() { return this.int(8, false); }
+  i64() { return this.int(8, true); }
+  u128() { return this.int(16, false); }
+  i128() { return this.int(16, true); }
+  bool() { return this.u8() !== 0; }
+
+  raw(length: number): Uint8Array {
+    const v = this.data.slice(this.offset, this.offset + length);
+    this.offset += length;
+    return v;
+  }
+
+  bytes() { return this.raw(this.u32()); }
+  string() { return new TextDecoder().decode(this.bytes()); }
+  pubkey() { return new PublicKey(this.raw(32)); }
+
+  vec<T>(item: (r: Reader) => T): T[] {
+    return this.array(this.u32(), item);
+  }
+
+  array<T>(length: number, item: (r: Reader) => T): T[] {
+    return Array.from({ length }, () => item(this));
+  }
+
+  option<T>(item: (r: Reader) => T): T | null {
+    return this.u8() === 0 ? null : item(this);
+  }
+}
+
+function checkDiscriminator(data: Uint8Array, discriminator: Uint8Array, name: string) {
+  if (!discriminator.every((b, i) => data[i] === b)) {
+    throw new Error(`Account is not a ${name}`);
+  }
+}
+
+export interface Vault {
+  owner: PublicKey;
+  stateBump: number;
+  authBump: number;
+  vaultBump: number;
+}
+
+export const VAULT_DISCRIMINATOR = new Uint8Array([211, 8, 232, 43, 2, 152, 117, 119]);
+
+export function decodeVault(data: Uint8Array): Vault {
+  checkDiscriminator(data, VAULT_DISCRIMINATOR, "Vault");
+  const r = new Reader(data.subarray(8));
+  return {
+    owner: r.pubkey(),
+    stateBump: r.u8(),
+    authBump: r.u8(),
+    vaultBump: r.u8(),
+  };
+}
+
+export function findStateAddress(owner: PublicKey, programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("state"), owner.toBuffer()], programId);
+}
+
+export function findAuthAddress(state: PublicKey, programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("auth"), state.toBuffer()], programId);
+}
+
+export function findVaultAddress(auth: PublicKey, programId = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("vault"), auth.toBuffer()], programId);
+}
+
+export interface InitializeAccounts {
+  owner: PublicKey;
+  /** `findStateAddress(owner)[0]` */
+  state: PublicKey;
+  /** `findAuthAddress(state)[0]` */
+  auth: PublicKey;
+  /** `findVaultAddress(auth)[0]` */
+  vault: PublicKey;
+}
+
+export function initialize(
+  accounts: InitializeAccounts,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([175, 175, 109, 31, 13, 152, 155, 237]));
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.state, isSigner: false, isWritable: true },
+      { pubkey: accounts.auth, isSigner: false, isWritable: false },
+      { pubkey: accounts.vault, isSigner: false, isWritable: true },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
+
+export interface DepositAccounts {
+  owner: PublicKey;
+  /** `findStateAddress(owner)[0]` */
+  state: PublicKey;
+  /** `findAuthAddress(state)[0]` */
+  auth: PublicKey;
+  /** `findVaultAddress(auth)[0]` */
+  vault: PublicKey;
+}
+
+export interface DepositArgs {
+  amount: bigint;
+}
+
+export function deposit(
+  accounts: DepositAccounts,
+  args: DepositArgs,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([242, 35, 198, 137, 82, 225, 242, 182]));
+  w.u64(args.amount);
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.state, isSigner: false, isWritable: false },
+      { pubkey: accounts.auth, isSigner: false, isWritable: false },
+      { pubkey: accounts.vault, isSigner: false, isWritable: true },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
+
+export interface WithdrawAccounts {
+  owner: PublicKey;
+  /** `findStateAddress(owner)[0]` */
+  state: PublicKey;
+  /** `findAuthAddress(state)[0]` */
+  auth: PublicKey;
+  /** `findVaultAddress(auth)[0]` */
+  vault: PublicKey;
+}
+
+export interface WithdrawArgs {
+  amount: bigint;
+}
+
+export function withdraw(
+  accounts: WithdrawAccounts,
+  args: WithdrawArgs,
+  programId = PROGRAM_ID,
+): TransactionInstruction {
+  const w = new Writer().raw(new Uint8Array([183, 18, 70, 156, 148, 109, 161, 34]));
+  w.u64(args.amount);
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.owner, isSigner: true, isWritable: true },
+      { pubkey: accounts.state, isSigner: false, isWritable: false },
+      { pubkey: accounts.auth, isSigner: false, isWritable: false },
+      { pubkey: accounts.vault, isSigner: false, isWritable: true },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: w.toBuffer(),
+  });
+}
@@ -12,32 +12,33 @@ pub mod vault_program {
         Ok(())
     }
     pub fn deposit(ctx: Context<DepositContext>, amount: u64) -> Result<()> {
-        let transfer_accounts = Transfer {
+        let cpi_accounts = Transfer {
             from: ctx.accounts.owner.to_account_info(),
             to: ctx.accounts.vault.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
-            transfer_accounts,
+            cpi_accounts,
         );
         transfer(cpi_ctx, amount)?;
         Ok(())
     }
     pub fn withdraw(ctx: Context<WithdrawContext>, amount: u64) -> Result<()> {
-        let transfer_accounts = Transfer {
+        let cpi_accounts = Transfer {
             from: ctx.accounts.vault.to_account_info(),
             to: ctx.accounts.owner.to_account_info(),
         };
-        let seeds = &[
-            b"vault",
-            ctx.accounts.auth.to_account_info().key.as_ref(),
-            &[ctx.accounts.state.vault_bump],
+        let signer_seeds: &[&[&[u8]]; 1] = &[
+            &[
+                b"vault",
+                ctx.accounts.state.to_account_info().key.as_ref(),
+                &[ctx.accounts.state.auth_bump],
+            ],
         ];
-        let pda_signer = &[&seeds[..]];
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
-            transfer_accounts,
-            pda_signer,
+            cpi_accounts,
+            signer_seeds,
         );
         transfer(cpi_ctx, amount)?;
         Ok(())
@@ -47,14 +48,7 @@ pub mod vault_program {
 pub struct InitializeContext<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(
-        init,
-        payer = owner,
-        space = 43,
-        seeds = [b"state",
-        owner.key().as_ref()],
-        bump,
-    )]
+    #[account(init, payer = owner, space = 43, seeds = [b"state", owner.key().as_ref()], bump)]
     pub state: Account<'info, Vault>,
     #[account(seeds = [b"auth", state.key().as_ref()], bump)]
     /// CHECK: This acc is safe
@@ -67,26 +61,26 @@ pub struct InitializeContext<'info> {
 pub struct DepositContext<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
+    #[account(seeds = [b"state", owner.key().as_ref()], bump = state.state_bump)]
+    pub state: Account<'info, Vault>,
     #[account(seeds = [b"auth", state.key().as_ref()], bump = state.auth_bump)]
     /// CHECK: This acc is safe
     pub auth: UncheckedAccount<'info>,
-    #[account(seeds = [b"state", owner.key().as_ref()], bump = state.state_bump)]
-    pub state: Account<'info, Vault>,
     #[account(mut, seeds = [b"vault", auth.key().as_ref()], bump = state.vault_bump)]
     pub vault: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 #[derive(Accounts)]
 pub struct WithdrawContext<'info> {
-    #[account(mut, seeds = [b"vault", auth.key().as_ref()], bump = state.vault_bump)]
-    pub vault: SystemAccount<'info>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    #[account(seeds = [b"state", owner.key().as_ref()], bump = state.state_bump)]
+    pub state: Account<'info, Vault>,
     #[account(seeds = [b"auth", state.key().as_ref()], bump = state.auth_bump)]
     /// CHECK: This acc is safe
     pub auth: UncheckedAccount<'info>,
-    #[account(seeds = [b"state", owner.key().as_ref()], bump = state.state_bump)]
-    pub state: Account<'info, Vault>,
+    #[account(mut, seeds = [b"vault", auth.key().as_ref()], bump = state.vault_bump)]
+    pub vault: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 #[account]
@@ -8,11 +8,17 @@ pub mod vote_program {
         Ok(())
     }
     pub fn upvote(ctx: Context<UpvoteContext>) -> Result<()> {
-        ctx.accounts.state.vote = ctx.accounts.state.vote + 1;
+        ctx
+            .accounts
+            .state
+            .vote = ctx.accounts.state.vote.checked_add(1).ok_or(ErrorCode::Overflow)?;
         Ok(())
     }
     pub fn downvote(ctx: Context<DownvoteContext>) -> Result<()> {
-        ctx.accounts.state.vote = ctx.accounts.state.vote - 1;
+        ctx
+            .accounts
+            .state
+            .vote = ctx.accounts.state.vote.checked_sub(1).ok_or(ErrorCode::Overflow)?;
         Ok(())
     }
 }
@@ -41,3 +47,8 @@ pub struct VoteState {
     pub vote: i64,
     pub bump: u8,
 }
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     env, fs,
     io::{BufRead, BufReader},
     path::{Path, PathBuf},
@@ -9,11 +9,11 @@ use std::{
 use anyhow::{Context, Result};
 use convert_case::{Case, Casing};
 use regex::{Regex, RegexBuilder};
-use swc_ecma_ast::Module;
 use toml::Value;
 
+use crate::ir::ProgramModule;
 use crate::parse_ts::parse_ts;
-use crate::transpiler::transpile;
+use crate::transpiler::transpile_crate;
 
 pub fn init(name: &String) {
     println!("Initializing project: {}", name);
@@ -23,7 +23,7 @@ pub fn init(name: &String) {
         return;
     }
 
-    if !is_valid_project_name(&name) {
+    if !is_valid_project_name(name) {
         println!("Invalid project name. Project names must start with a letter and contain only alphanumeric characters and hyphens.");
         return;
     }
@@ -88,7 +88,7 @@ pub fn init(name: &String) {
         ts_programs_src_path.join(format!("{}.ts", ts_program_file_name));
     fs::write(
         &ts_programs_src_program_path,
-        get_default_program_content(&name, &program_id),
+        get_default_program_content(name, program_id),
     )
     .unwrap_or_else(|_| {
         panic!(
@@ -117,9 +117,22 @@ pub fn build_workspace() -> Result<()> {
         return Err(anyhow::anyhow!("programs directory not found"));
     }
 
-    // Process each program in the programs directory
-    for program_entry in fs::read_dir(&programs_dir)? {
-        let program_dir = program_entry?.path();
+    // Process each program in the programs directory, in a stable order
+    let mut program_dirs = fs::read_dir(&programs_dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    program_dirs.sort();
+
+    // The packages of the workspace, which CPIs between its programs depend on by path
+    let workspace_programs: Vec<(String, PathBuf)> = program_dirs
+        .iter()
+        .filter_map(|dir| {
+            let name = get_program_name_from_cargo(&dir.join("Cargo.toml")).ok()?;
+            Some((name, dir.clone()))
+        })
+        .collect();
+
+    for program_dir in program_dirs {
         if !program_dir.is_dir() {
             continue;
         }
@@ -153,12 +166,12 @@ pub fn build_workspace() -> Result<()> {
             continue;
         }
 
-        // Compile TypeScript to Rust
-        let rs_file = src_dir.join("lib.rs");
-        println!("Compiling {} to {}", ts_file.display(), rs_file.display());
+        // Compile TypeScript to the Rust crate
+        println!("Compiling {} to {}", ts_file.display(), src_dir.display());
 
-        let module: Module = parse_ts(&ts_file.to_string_lossy().to_string());
-        transpile(&module, &rs_file.to_string_lossy().to_string())?;
+        let (module, source_map) = parse_ts(&ts_file.to_string_lossy())?;
+        let program = transpile_crate(&module, source_map, &src_dir, None)?;
+        add_cpi_dependencies(&cargo_path, &program, &workspace_programs)?;
 
         println!("Successfully compiled {}", program_name);
     }
@@ -244,10 +257,10 @@ pub fn sync_program_ids() -> Result<()> {
     Ok(())
 }
 
-fn extract_program_ids(anchor_toml: &str) -> Result<HashMap<String, String>> {
+fn extract_program_ids(anchor_toml: &str) -> Result<BTreeMap<String, String>> {
     let toml_value: Value = anchor_toml.parse().context("Failed to parse Anchor.toml")?;
 
-    let mut program_ids = HashMap::new();
+    let mut program_ids = BTreeMap::new();
 
     if let Some(programs) = toml_value
         .get("programs")
@@ -311,11 +324,66 @@ fn get_program_name_from_cargo(cargo_path: &Path) -> Result<String> {
     Ok(package_name.to_string())
 }
 
+/// Adds the crates of the programs `program` invokes to its Cargo.toml, with the `cpi`
+/// feature that generates their CPI helpers. Programs of the workspace are depended on by
+/// path, others by the version in their IDL.
+fn add_cpi_dependencies(
+    cargo_path: &Path,
+    program: &ProgramModule,
+    workspace_programs: &[(String, PathBuf)],
+) -> Result<()> {
+    let content = fs::read_to_string(cargo_path).context("Failed to read Cargo.toml")?;
+    let cargo_toml: Value = content.parse().context("Failed to parse Cargo.toml")?;
+    let dependencies = cargo_toml.get("dependencies").and_then(|d| d.as_table());
+
+    let mut lines = vec![];
+    for external in program.external_programs() {
+        let workspace_program = workspace_programs
+            .iter()
+            .find(|(name, _)| name.to_case(Case::Snake) == external.crate_name);
+        let (name, source) = match workspace_program {
+            Some((name, dir)) => {
+                let dir = dir.file_name().unwrap_or_default().to_string_lossy();
+                (name.clone(), format!("path = \"../{}\"", dir))
+            }
+            None => (
+                external.crate_name.to_case(Case::Kebab),
+                format!("version = \"{}\"", external.version),
+            ),
+        };
+        let already_added = dependencies
+            .is_some_and(|d| d.contains_key(&name) || d.contains_key(&external.crate_name));
+        if !already_added {
+            println!("Adding dependency on {}", name);
+            lines.push(format!("{} = {{ {}, features = [\"cpi\"] }}", name, source));
+        }
+    }
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    // New dependencies go right under the `[dependencies]` header
+    let header_end = content
+        .find("[dependencies]")
+        .and_then(|index| Some(index + content[index..].find('\n')? + 1));
+    let new_content = match header_end {
+        Some(at) => format!("{}{}\n{}", &content[..at], lines.join("\n"), &content[at..]),
+        None => format!(
+            "{}\n\n[dependencies]\n{}\n",
+            content.trim_end(),
+            lines.join("\n")
+        ),
+    };
+    fs::write(cargo_path, new_content).context("Failed to write Cargo.toml")?;
+
+    Ok(())
+}
+
 fn anchor_installed() -> bool {
     Command::new("anchor")
         .arg("--version")
         .output()
-        .map_or(false, |output| output.status.success())
+        .is_ok_and(|output| output.status.success())
 }
 
 fn is_valid_project_name(name: &str) -> bool {
@@ -348,6 +416,8 @@ fn execute_cmd(cmd: &mut Command) {
     for line in lines {
         println!("{}", line.unwrap());
     }
+
+    child.wait().expect("Failed to wait on command");
 }
 
 fn get_default_program_content(program_name: &str, program_id: &str) -> String {
@@ -0,0 +1,1430 @@
+//! Generates the Rust source of an Anchor program from the `ir`.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
+use quote::quote;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use crate::{
+    ir::{
+        AccountType, ArgPassing, ArithmeticMode, BinaryOp, BuiltinProgram, Bump, ConstantValue,
+        CpiCall, CpiProgram, Expression, InstructionAccount, InstructionArgument, OptionalValue,
+        ProgramAccount, ProgramAccountField, ProgramConstant, ProgramEnum, ProgramError,
+        ProgramEvent, ProgramInstruction, ProgramModule, ProgramStruct, Seed, Statement, UnaryOp,
+    },
+    ts_types::rs_type_from_str,
+};
+
+// Sorted maps keep the generated `use` items the same from one run to the next
+type SubMember = BTreeMap<String, Option<String>>; // submember_name : alias
+type Member = BTreeMap<String, SubMember>; // member_name : submembers
+type ProgramImport = BTreeMap<String, Member>; // src_pkg : members
+
+/// Where an expression is rendered, which decides how accounts are reached.
+#[derive(Clone, Copy)]
+enum Scope<'a> {
+    /// Inside the instruction handler, through `ctx`. Optional accounts are only used
+    /// inside an `if let Some(...)` and are reached through its binding instead.
+    Handler(&'a [String]),
+    /// Inside an `#[account(...)]` constraint of the accounts struct.
+    Constraint,
+}
+
+/// The path to `account` from `scope`.
+fn account_path(account: &str, scope: Scope) -> TokenStream {
+    let name = ident(account);
+    match scope {
+        Scope::Handler(optional) if !optional.iter().any(|a| a == account) => {
+            quote!(ctx.accounts.#name)
+        }
+        _ => quote!(#name),
+    }
+}
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name, Span::call_site())
+}
+
+pub fn generate(program: &ProgramModule) -> Result<TokenStream> {
+    AnchorGenerator::default().program(program)
+}
+
+/// The files of the program as an Anchor crate in the standard layout, by their path in
+/// `src`: `lib.rs` dispatching to `instructions/`, with `state/`, `constants.rs`,
+/// `errors.rs` and `events.rs` next to it when the program has any.
+pub fn generate_crate(program: &ProgramModule) -> Result<Vec<(String, TokenStream)>> {
+    let mut files: Vec<(String, TokenStream)> = vec![];
+    let mut modules: Vec<&str> = vec![];
+    if !program.constants.is_empty() {
+        let constants = program
+            .constants
+            .iter()
+            .map(constant)
+            .collect::<Result<Vec<TokenStream>>>()?;
+        modules.push("constants");
+        files.push((
+            String::from("constants.rs"),
+            quote! {
+                use anchor_lang::prelude::*;
+                #(#constants)*
+            },
+        ));
+    }
+    if !program.errors.is_empty() {
+        let errors = error_code(&program.errors);
+        modules.push("errors");
+        files.push((
+            String::from("errors.rs"),
+            quote! {
+                use anchor_lang::prelude::*;
+                #errors
+            },
+        ));
+    }
+    if !program.events.is_empty() {
+        let mut generator = AnchorGenerator::default();
+        let events = program
+            .events
+            .iter()
+            .map(|e| generator.event(e))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let crate_imports =
+            field_imports(program, program.events.iter().flat_map(|e| e.fields.iter()))?;
+        modules.push("events");
+        files.push((
+            String::from("events.rs"),
+            quote! {
+                use anchor_lang::prelude::*;
+                #crate_imports
+                #(#events)*
+            },
+        ));
+    }
+
+    modules.push("instructions");
+    let mut instruction_mods: Vec<Ident> = vec![];
+    let mut dispatchers: Vec<TokenStream> = vec![];
+    for ix in program.instructions.iter() {
+        // Each file only imports what it uses
+        let mut generator = AnchorGenerator::default();
+        let accounts_struct = generator.accounts_struct(ix)?;
+        let handler = generator.handler(ix, ident("handler"))?;
+        let item = quote! {
+            #accounts_struct
+            #handler
+        };
+        let imports = generator.imports_to_tokens();
+        let crate_imports = crate_imports(program, &item);
+        files.push((
+            format!("instructions/{}.rs", ix.name),
+            quote! {
+                use anchor_lang::prelude::*;
+                #imports
+                #crate_imports
+                #item
+            },
+        ));
+
+        let name = ident(&ix.name);
+        let ctx_name = ident(&format!("{}Context", ix.name.to_case(Case::Pascal)));
+        let params = handler_params(ix)?;
+        let args = ix.args.iter().map(|a| ident(&a.name));
+        dispatchers.push(quote! {
+            pub fn #name(ctx: Context<#ctx_name>, #(#params),*) -> Result<()> {
+                instructions::#name::handler(ctx, #(#args),*)
+            }
+        });
+        instruction_mods.push(name);
+    }
+    // Every instruction has a `handler`, which `lib.rs` calls by its full path
+    let instructions_mod = mod_file(&instruction_mods);
+    files.push((
+        String::from("instructions/mod.rs"),
+        quote! {
+            #![allow(ambiguous_glob_reexports)]
+            #instructions_mod
+        },
+    ));
+
+    if !program.accounts.is_empty() || !program.structs.is_empty() || !program.enums.is_empty() {
+        let mut state_mods: Vec<Ident> = vec![];
+        for account in program.accounts.iter() {
+            let name = account.name.to_case(Case::Snake);
+            let mut generator = AnchorGenerator::default();
+            let item = generator.program_account(account)?;
+            let imports = generator.imports_to_tokens();
+            let crate_imports = field_imports(program, account.fields.iter())?;
+            files.push((
+                format!("state/{}.rs", name),
+                quote! {
+                    use anchor_lang::prelude::*;
+                    #imports
+                    #crate_imports
+                    #item
+                },
+            ));
+            state_mods.push(ident(&name));
+        }
+        for s in program.structs.iter() {
+            let name = s.name.to_case(Case::Snake);
+            let item = program_struct(s)?;
+            let crate_imports = field_imports(program, s.fields.iter())?;
+            files.push((
+                format!("state/{}.rs", name),
+                quote! {
+                    use anchor_lang::prelude::*;
+                    #crate_imports
+                    #item
+                },
+            ));
+            state_mods.push(ident(&name));
+        }
+        for e in program.enums.iter() {
+            let name = e.name.to_case(Case::Snake);
+            let item = program_enum(e);
+            files.push((
+                format!("state/{}.rs", name),
+                quote! {
+                    use anchor_lang::prelude::*;
+                    #item
+                },
+            ));
+            state_mods.push(ident(&name));
+        }
+        modules.push("state");
+        files.push((String::from("state/mod.rs"), mod_file(&state_mods)));
+    }
+
+    let program_name = ident(&program.name.to_case(Case::Snake));
+    let program_id = Literal::string(&program.id);
+    // `ErrorCode` is also in Anchor's prelude, so errors are imported by path instead
+    let reexports: Vec<Ident> = modules
+        .iter()
+        .filter(|m| **m != "errors")
+        .map(|m| ident(m))
+        .collect();
+    let modules: Vec<Ident> = modules.into_iter().map(ident).collect();
+    files.insert(
+        0,
+        (
+            String::from("lib.rs"),
+            quote! {
+                use anchor_lang::prelude::*;
+
+                #(pub mod #modules;)*
+
+                #(pub use #reexports::*;)*
+
+                declare_id!(#program_id);
+
+                #[program]
+                pub mod #program_name {
+                    use super::*;
+
+                    #(#dispatchers)*
+                }
+            },
+        ),
+    );
+    Ok(files)
+}
+
+/// Declares and re-exports `modules`, as Anchor needs the accounts structs at the root.
+fn mod_file(modules: &[Ident]) -> TokenStream {
+    quote! {
+        #(pub mod #modules;)*
+
+        #(pub use #modules::*;)*
+    }
+}
+
+/// `use crate::...` for the program's own items referred to in `item`.
+fn crate_imports(program: &ProgramModule, item: &TokenStream) -> TokenStream {
+    let mut used = BTreeSet::new();
+    collect_idents(item.clone(), &mut used);
+    let mut paths: Vec<TokenStream> = vec![];
+    for c in program.constants.iter().filter(|c| used.contains(&c.name)) {
+        let name = ident(&c.name);
+        paths.push(quote!(constants::#name));
+    }
+    if !program.errors.is_empty() && used.contains("ErrorCode") {
+        paths.push(quote!(errors::ErrorCode));
+    }
+    for e in program.events.iter().filter(|e| used.contains(&e.name)) {
+        let name = ident(&e.name);
+        paths.push(quote!(events::#name));
+    }
+    for a in program.accounts.iter().filter(|a| used.contains(&a.name)) {
+        let name = ident(&a.name);
+        paths.push(quote!(state::#name));
+    }
+    for s in program.structs.iter().filter(|s| used.contains(&s.name)) {
+        let name = ident(&s.name);
+        paths.push(quote!(state::#name));
+    }
+    for e in program.enums.iter().filter(|e| used.contains(&e.name)) {
+        let name = ident(&e.name);
+        paths.push(quote!(state::#name));
+    }
+    match paths.is_empty() {
+        true => quote! {},
+        false => quote! { use crate::{#(#paths),*}; },
+    }
+}
+
+/// `crate_imports` for the types of `fields`, which leaves out the item they belong to.
+fn field_imports<'a>(
+    program: &ProgramModule,
+    fields: impl Iterator<Item = &'a ProgramAccountField>,
+) -> Result<TokenStream> {
+    let types = fields
+        .map(|f| rs_type_from_str(&f.of_type))
+        .collect::<Result<Vec<TokenStream>>>()?;
+    Ok(crate_imports(program, &quote!(#(#types)*)))
+}
+
+fn collect_idents(tokens: TokenStream, found: &mut BTreeSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(i) => {
+                found.insert(i.to_string());
+            }
+            TokenTree::Group(g) => collect_idents(g.stream(), found),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct AnchorGenerator {
+    imports: ProgramImport,
+}
+
+impl AnchorGenerator {
+    /// Records `use src_pkg::member::sub_member` and returns the name to refer to it by.
+    fn add_import(&mut self, src_pkg: &str, member_name: &str, sub_member_name: &str) -> Ident {
+        let mut alias: Option<String> = None;
+        if sub_member_name == "Transfer" && member_name == "token" {
+            alias = Some("TransferSPL".to_string());
+        }
+        if sub_member_name == "transfer" && member_name == "token" {
+            alias = Some("transfer_spl".to_string());
+        }
+        let name = ident(alias.as_deref().unwrap_or(sub_member_name));
+        self.imports
+            .entry(src_pkg.to_string())
+            .or_default()
+            .entry(member_name.to_string())
+            .or_default()
+            .entry(sub_member_name.to_string())
+            .or_insert(alias);
+        name
+    }
+
+    fn program(&mut self, program: &ProgramModule) -> Result<TokenStream> {
+        let program_name = ident(&program.name.to_case(Case::Snake));
+        let program_id = Literal::string(&program.id);
+        let serialized_instructions = program
+            .instructions
+            .iter()
+            .map(|ix| self.instruction(ix))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let serialized_account_structs = program
+            .instructions
+            .iter()
+            .map(|ix| self.accounts_struct(ix))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let serialized_accounts = program
+            .accounts
+            .iter()
+            .map(|a| self.program_account(a))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let serialized_structs = program
+            .structs
+            .iter()
+            .map(program_struct)
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let serialized_enums: Vec<TokenStream> = program.enums.iter().map(program_enum).collect();
+        let serialized_events = program
+            .events
+            .iter()
+            .map(|e| self.event(e))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let constants = program
+            .constants
+            .iter()
+            .map(constant)
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let errors = error_code(&program.errors);
+        let imports = self.imports_to_tokens();
+
+        Ok(quote! {
+            use anchor_lang::prelude::*;
+            #imports
+            declare_id!(#program_id);
+
+            #(#constants)*
+
+            #[program]
+            pub mod #program_name {
+                use super::*;
+
+                #(#serialized_instructions)*
+            }
+
+            #(#serialized_account_structs)*
+
+            #(#serialized_accounts)*
+
+            #(#serialized_structs)*
+
+            #(#serialized_enums)*
+
+            #(#serialized_events)*
+
+            #errors
+        })
+    }
+
+    fn imports_to_tokens(&self) -> TokenStream {
+        let mut imports_vec: Vec<TokenStream> = vec![];
+        for (src_pkg, members) in self.imports.iter() {
+            let src_pkg_ident = ident(src_pkg);
+
+            let mut member_tokens: Vec<TokenStream> = vec![];
+            for (member_name, sub_members) in members.iter() {
+                let member_name_ident = ident(member_name);
+                let mut sub_member_tokens: Vec<TokenStream> = vec![];
+                for (sub_member_name, alias) in sub_members {
+                    let sub_member_name_ident = ident(sub_member_name);
+                    match alias {
+                        None => sub_member_tokens.push(quote! {#sub_member_name_ident}),
+                        Some(alias) => {
+                            let alias_ident = ident(alias);
+                            sub_member_tokens.push(quote! {#sub_member_name_ident as #alias_ident});
+                        }
+                    }
+                }
+
+                member_tokens.push(quote!(#member_name_ident :: {#(#sub_member_tokens),*}))
+            }
+            imports_vec.push(quote! {use #src_pkg_ident :: {#(#member_tokens),*};});
+        }
+        quote! {#(#imports_vec)*}
+    }
+
+    fn program_account(&mut self, account: &ProgramAccount) -> Result<TokenStream> {
+        let struct_name = ident(&account.name);
+        let fields = struct_fields(&account.fields)?;
+
+        Ok(quote! {
+            #[account]
+            pub struct #struct_name {
+                #(#fields),*
+            }
+        })
+    }
+
+    fn event(&mut self, event: &ProgramEvent) -> Result<TokenStream> {
+        let struct_name = ident(&event.name);
+        let fields = struct_fields(&event.fields)?;
+
+        Ok(quote! {
+            #[event]
+            pub struct #struct_name {
+                #(#fields),*
+            }
+        })
+    }
+
+    fn instruction(&mut self, ix: &ProgramInstruction) -> Result<TokenStream> {
+        self.handler(ix, ident(&ix.name))
+    }
+
+    /// The function running the body of `ix`, called `name`.
+    fn handler(&mut self, ix: &ProgramInstruction, name: Ident) -> Result<TokenStream> {
+        let ctx_name = ident(&format!("{}Context", ix.name.to_case(Case::Pascal)));
+        let args = handler_params(ix)?;
+        let optional: Vec<String> = ix
+            .accounts
+            .iter()
+            .filter(|a| a.optional)
+            .map(|a| a.name.clone())
+            .collect();
+        let scope = Scope::Handler(&optional);
+        let stmts = self.statements(&ix.body, scope)?;
+        Ok(quote! {
+            pub fn #name (ctx: Context<#ctx_name>, #(#args),*) -> Result<()> {
+                #(#stmts)*
+                Ok(())
+            }
+        })
+    }
+
+    fn statements(&mut self, stmts: &[Statement], scope: Scope) -> Result<Vec<TokenStream>> {
+        stmts.iter().map(|s| self.statement(s, scope)).collect()
+    }
+
+    fn statement(&mut self, stmt: &Statement, scope: Scope) -> Result<TokenStream> {
+        Ok(match stmt {
+            Statement::Let {
+                name,
+                mutable,
+                of_type,
+                value,
+            } => {
+                let name = ident(name);
+                let mutability = mutable.then(|| quote!(mut));
+                let of_type = match of_type {
+                    Some(ty) => {
+                        let ty = rs_type_from_str(ty)?;
+                        Some(quote!(: #ty))
+                    }
+                    None => None,
+                };
+                let value = expression(value, scope)?;
+                quote! { let #mutability #name #of_type = #value; }
+            }
+            Statement::Assign { target, value } => {
+                let target = expression(target, scope)?;
+                let value = expression(value, scope)?;
+                quote! { #target = #value; }
+            }
+            Statement::Cpi(cpi) => self.cpi(cpi, scope)?,
+            Statement::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let cond = expression(cond, scope)?;
+                let then = self.statements(then, scope)?;
+                let otherwise = self.else_branch(otherwise.as_deref(), scope)?;
+                quote! {
+                    if #cond {
+                        #(#then)*
+                    } #otherwise
+                }
+            }
+            Statement::IfSome {
+                value,
+                then,
+                otherwise,
+            } => {
+                let (name, source) = match value {
+                    OptionalValue::Account { name, mutable } => {
+                        let name = ident(name);
+                        match mutable {
+                            true => (name.clone(), quote!(&mut ctx.accounts.#name)),
+                            false => (name.clone(), quote!(&ctx.accounts.#name)),
+                        }
+                    }
+                    OptionalValue::Arg { name, by_ref } => {
+                        let name = ident(name);
+                        match by_ref {
+                            true => (name.clone(), quote!(&#name)),
+                            false => (name.clone(), quote!(#name)),
+                        }
+                    }
+                };
+                let then = self.statements(then, scope)?;
+                let otherwise = self.else_branch(otherwise.as_deref(), scope)?;
+                quote! {
+                    if let Some(#name) = #source {
+                        #(#then)*
+                    } #otherwise
+                }
+            }
+            Statement::Match {
+                value,
+                arms,
+                otherwise,
+            } => {
+                let value = expression(value, scope)?;
+                let mut arm_tokens: Vec<TokenStream> = vec![];
+                for (values, body) in arms.iter() {
+                    let values = values
+                        .iter()
+                        .map(|v| expression(v, scope))
+                        .collect::<Result<Vec<TokenStream>>>()?;
+                    let body = self.statements(body, scope)?;
+                    arm_tokens.push(quote! { #(#values)|* => { #(#body)* } });
+                }
+                if let Some(otherwise) = otherwise {
+                    let body = self.statements(otherwise, scope)?;
+                    arm_tokens.push(quote! { _ => { #(#body)* } });
+                }
+                quote! {
+                    match #value {
+                        #(#arm_tokens)*
+                    }
+                }
+            }
+            Statement::Block(stmts) => {
+                let stmts = self.statements(stmts, scope)?;
+                quote! { { #(#stmts)* } }
+            }
+            Statement::Require { cond, error } => {
+                let cond = expression(cond, scope)?;
+                let error = ident(error);
+                quote! { require!(#cond, ErrorCode::#error); }
+            }
+            Statement::Throw(error) => {
+                let error = ident(error);
+                quote! { return err!(ErrorCode::#error); }
+            }
+            Statement::Return => quote! { return Ok(()); },
+            Statement::Emit { event, fields } => {
+                let event = ident(event);
+                let fields = fields
+                    .iter()
+                    .map(|(field, value)| match value {
+                        // Field init shorthand, e.g. `amount` instead of `amount: amount`
+                        Expression::Arg(name) | Expression::Local(name) if name == field => {
+                            let field = ident(field);
+                            Ok(quote!(#field))
+                        }
+                        _ => {
+                            let field = ident(field);
+                            let value = expression(value, scope)?;
+                            Ok(quote!(#field: #value))
+                        }
+                    })
+                    .collect::<Result<Vec<TokenStream>>>()?;
+                quote! { emit!(#event { #(#fields),* }); }
+            }
+        })
+    }
+
+    fn else_branch(
+        &mut self,
+        otherwise: Option<&[Statement]>,
+        scope: Scope,
+    ) -> Result<TokenStream> {
+        Ok(match otherwise {
+            None => quote! {},
+            // Keep `else if` chains flat
+            Some([nested @ (Statement::If { .. } | Statement::IfSome { .. })]) => {
+                let nested = self.statement(nested, scope)?;
+                quote! { else #nested }
+            }
+            Some(stmts) => {
+                let stmts = self.statements(stmts, scope)?;
+                quote! { else { #(#stmts)* } }
+            }
+        })
+    }
+
+    fn cpi(&mut self, cpi: &CpiCall, scope: Scope) -> Result<TokenStream> {
+        let (src_pkg, member, program) = match &cpi.program {
+            CpiProgram::System => ("anchor_lang", "system_program", quote!(system_program)),
+            CpiProgram::Token => ("anchor_spl", "token", quote!(token_program)),
+            CpiProgram::TokenInterface => ("anchor_spl", "token_interface", quote!(token_program)),
+            CpiProgram::AssociatedToken => (
+                "anchor_spl",
+                "associated_token",
+                quote!(associated_token_program),
+            ),
+            CpiProgram::External(p) => {
+                let program = ident(&p.account_name());
+                (p.crate_name.as_str(), "cpi", quote!(#program))
+            }
+        };
+        let (accounts_struct, function) = match &cpi.program {
+            // Spelled out in full, as programs may have instructions of the same names
+            CpiProgram::External(p) => {
+                let src_pkg = ident(src_pkg);
+                let member = ident(member);
+                let accounts_struct = match p.poseidon {
+                    true => format!("{}Context", cpi.instruction.to_case(Case::Pascal)),
+                    false => cpi.instruction.to_case(Case::Pascal),
+                };
+                let accounts_struct = ident(&accounts_struct);
+                let function = ident(&cpi.instruction);
+                (
+                    quote!(#src_pkg::#member::accounts::#accounts_struct),
+                    quote!(#src_pkg::#member::#function),
+                )
+            }
+            _ => {
+                // Both associated token instructions take the accounts of `create`
+                let accounts_struct = match cpi.instruction.as_str() {
+                    "create_idempotent" => String::from("Create"),
+                    instruction => instruction.to_case(Case::Pascal),
+                };
+                let accounts_struct = self.add_import(src_pkg, member, &accounts_struct);
+                let function = self.add_import(src_pkg, member, &cpi.instruction);
+                (quote!(#accounts_struct), quote!(#function))
+            }
+        };
+        let mut fields: Vec<TokenStream> = cpi
+            .accounts
+            .iter()
+            .map(|(field, account)| {
+                let field = ident(field);
+                let account = account_path(account, scope);
+                quote! { #field: #account.to_account_info() }
+            })
+            .collect();
+        for (field, account) in cpi.optional_accounts.iter() {
+            let field = ident(field);
+            fields.push(match account {
+                Some(account) => {
+                    let account = account_path(account, scope);
+                    quote! { #field: Some(#account.to_account_info()) }
+                }
+                None => quote! { #field: None },
+            });
+        }
+        let args = cpi
+            .args
+            .iter()
+            .map(|(arg, passing)| {
+                let arg = expression(arg, scope)?;
+                Ok(match passing {
+                    ArgPassing::Value => arg,
+                    ArgPassing::Ref => quote!(&#arg),
+                    ArgPassing::Owned => quote!(#arg.to_string()),
+                })
+            })
+            .collect::<Result<Vec<TokenStream>>>()?;
+        let cpi_accounts = quote! {
+            let cpi_accounts = #accounts_struct {
+                #(#fields),*
+            };
+        };
+        Ok(match &cpi.signer_seeds {
+            Some(seeds) => {
+                let seeds = seeds
+                    .iter()
+                    .map(|s| seed(s, scope))
+                    .collect::<Result<Vec<TokenStream>>>()?;
+                quote! {
+                    #cpi_accounts
+                    let signer_seeds: &[&[&[u8]]; 1] = &[&[#(#seeds),*]];
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.#program.to_account_info(),
+                        cpi_accounts,
+                        signer_seeds,
+                    );
+                    #function(cpi_ctx, #(#args),*)?;
+                }
+            }
+            None => quote! {
+                #cpi_accounts
+                let cpi_ctx = CpiContext::new(ctx.accounts.#program.to_account_info(), cpi_accounts);
+                #function(cpi_ctx, #(#args),*)?;
+            },
+        })
+    }
+
+    fn accounts_struct(&mut self, ix: &ProgramInstruction) -> Result<TokenStream> {
+        let ctx_name = ident(&format!("{}Context", ix.name.to_case(Case::Pascal)));
+        let mut accounts = ix
+            .accounts
+            .iter()
+            .map(|a| self.account(a))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        for program in ix.external_programs.iter() {
+            let name = ident(&program.account_name());
+            let src_pkg = ident(&program.crate_name);
+            let ty = ident(&program.crate_name.to_case(Case::Pascal));
+            accounts.push(quote! {
+                pub #name: Program<'info, #src_pkg::program::#ty>,
+            })
+        }
+        for program in ix.programs() {
+            let name = ident(program.account_name());
+            let ty = match program {
+                BuiltinProgram::AssociatedToken => {
+                    let ty = self.add_import("anchor_spl", "associated_token", "AssociatedToken");
+                    quote!(Program<'info, #ty>)
+                }
+                BuiltinProgram::Token => {
+                    let ty = self.add_import("anchor_spl", "token", "Token");
+                    quote!(Program<'info, #ty>)
+                }
+                BuiltinProgram::TokenInterface => {
+                    let ty = self.add_import("anchor_spl", "token_interface", "TokenInterface");
+                    quote!(Interface<'info, #ty>)
+                }
+                BuiltinProgram::System => quote!(Program<'info, System>),
+            };
+            accounts.push(quote! {
+                pub #name: #ty,
+            })
+        }
+        let ix_attributes = instruction_attribute(ix)?;
+        let info_token_stream = match accounts.is_empty() {
+            true => quote! {},
+            false => quote! {<'info>},
+        };
+        Ok(quote! {
+            #[derive(Accounts)]
+            #ix_attributes
+            pub struct #ctx_name #info_token_stream {
+                #(#accounts)*
+            }
+        })
+    }
+
+    fn account(&mut self, account: &InstructionAccount) -> Result<TokenStream> {
+        let name = ident(&account.name);
+        let of_type = match &account.of_type {
+            AccountType::Signer => quote! { Signer<'info> },
+            AccountType::UncheckedAccount => quote! { UncheckedAccount<'info> },
+            AccountType::SystemAccount => quote! { SystemAccount<'info> },
+            AccountType::TokenAccount | AccountType::AssociatedTokenAccount => {
+                let ty = self.add_import("anchor_spl", "token", "TokenAccount");
+                quote! { Account<'info, #ty> }
+            }
+            AccountType::Mint => {
+                let ty = self.add_import("anchor_spl", "token", "Mint");
+                quote! { Account<'info, #ty> }
+            }
+            AccountType::InterfaceTokenAccount | AccountType::InterfaceAssociatedTokenAccount => {
+                let ty = self.add_import("anchor_spl", "token_interface", "TokenAccount");
+                quote! { InterfaceAccount<'info, #ty> }
+            }
+            AccountType::InterfaceMint => {
+                let ty = self.add_import("anchor_spl", "token_interface", "Mint");
+                quote! { InterfaceAccount<'info, #ty> }
+            }
+            AccountType::Custom(ty) => {
+                let ty = ident(ty);
+                quote! { Account<'info, #ty> }
+            }
+        };
+        let constraints = constraints(account)?;
+        let attribute = match constraints.is_empty() {
+            true => quote! {},
+            false => quote! { #[account(#(#constraints),*)] },
+        };
+        let check = match account.of_type {
+            AccountType::UncheckedAccount => quote! {
+                /// CHECK: This acc is safe
+            },
+            _ => quote! {},
+        };
+        let of_type = match account.optional {
+            true => quote!(Option<#of_type>),
+            false => of_type,
+        };
+        Ok(quote!(
+            #attribute
+            #check
+            pub #name: #of_type,
+        ))
+    }
+}
+
+/// The items of the `#[account(...)]` attribute, in the order Anchor users write them.
+fn constraints(account: &InstructionAccount) -> Result<Vec<TokenStream>> {
+    let constraints = &account.constraints;
+    let mut items: Vec<TokenStream> = vec![];
+    match &constraints.init {
+        Some(init) => {
+            items.push(match init.if_needed {
+                true => quote!(init_if_needed),
+                false => quote!(init),
+            });
+            let payer = ident(&init.payer);
+            items.push(quote!(payer = #payer));
+            if let Some(space) = init.space {
+                let space = Literal::u32_unsuffixed(space);
+                items.push(quote!(space = #space));
+            }
+        }
+        None if constraints.is_mut => items.push(quote!(mut)),
+        None => {}
+    }
+    if let Some(seeds) = &constraints.seeds {
+        let seeds = seeds
+            .iter()
+            .map(|s| seed(s, Scope::Constraint))
+            .collect::<Result<Vec<TokenStream>>>()?;
+        items.push(quote!(seeds = [#(#seeds),*]));
+    }
+    if let Some(token) = &constraints.token {
+        let mint = ident(&token.mint);
+        let authority = ident(&token.authority);
+        // Token-2022 accounts name the program they belong to
+        let token_program = account
+            .of_type
+            .is_interface()
+            .then(|| ident("token_program"));
+        if token.is_ata {
+            items.push(quote!(associated_token::mint = #mint));
+            items.push(quote!(associated_token::authority = #authority));
+            if let Some(token_program) = &token_program {
+                items.push(quote!(associated_token::token_program = #token_program));
+            }
+        } else {
+            items.push(quote!(token::mint = #mint));
+            items.push(quote!(token::authority = #authority));
+            if let Some(token_program) = &token_program {
+                items.push(quote!(token::token_program = #token_program));
+            }
+        }
+    }
+    if let Some(mint) = &constraints.mint {
+        if let Some(decimals) = &mint.decimals {
+            let decimals = expression(decimals, Scope::Constraint)?;
+            items.push(quote!(mint::decimals = #decimals));
+        }
+        if let Some(authority) = &mint.authority {
+            let authority = ident(authority);
+            items.push(quote!(mint::authority = #authority));
+        }
+        if let Some(freeze_authority) = &mint.freeze_authority {
+            let freeze_authority = ident(freeze_authority);
+            items.push(quote!(mint::freeze_authority = #freeze_authority));
+        }
+        // Only initializing a mint takes the token program
+        if account.of_type.is_interface() && constraints.init.is_some() {
+            items.push(quote!(mint::token_program = token_program));
+        }
+    }
+    for has_one in constraints.has_one.iter() {
+        let has_one = ident(has_one);
+        items.push(quote!(has_one = #has_one));
+    }
+    if let Some(address) = &constraints.address {
+        let address = expression(address, Scope::Constraint)?;
+        items.push(quote!(address = #address));
+    }
+    if let Some(owner) = &constraints.owner {
+        let owner = expression(owner, Scope::Constraint)?;
+        items.push(quote!(owner = #owner));
+    }
+    if constraints.executable {
+        items.push(quote!(executable));
+    }
+    for constraint in constraints.constraint.iter() {
+        let cond = expression(&constraint.cond, Scope::Constraint)?;
+        match &constraint.error {
+            Some(error) => {
+                let error = ident(error);
+                items.push(quote!(constraint = #cond @ ErrorCode::#error));
+            }
+            None => items.push(quote!(constraint = #cond)),
+        }
+    }
+    match &constraints.bump {
+        Some(Bump::Canonical) => items.push(quote!(bump)),
+        Some(Bump::Stored(e)) => {
+            let e = expression(e, Scope::Constraint)?;
+            items.push(quote!(bump = #e));
+        }
+        None => {}
+    }
+    if let Some(realloc) = &constraints.realloc {
+        let space = expression(&realloc.space, Scope::Constraint)?;
+        let payer = ident(&realloc.payer);
+        let zero = realloc.zero;
+        items.push(quote!(realloc = #space));
+        items.push(quote!(realloc::payer = #payer));
+        items.push(quote!(realloc::zero = #zero));
+    }
+    if let Some(close) = &constraints.close {
+        let close = ident(close);
+        items.push(quote!(close = #close));
+    }
+    Ok(items)
+}
+
+/// `#[instruction(...)]` exposes the handler arguments used by the account constraints.
+/// Anchor deserializes them in order, so every argument up to the last one used is listed.
+fn instruction_attribute(ix: &ProgramInstruction) -> Result<TokenStream> {
+    let mut used: Vec<String> = vec![];
+    for account in ix.accounts.iter() {
+        for s in account.constraints.seeds.iter().flatten() {
+            if let Seed::Bytes(e) | Seed::Bump(e) = s {
+                referenced_args(e, &mut used);
+            }
+        }
+        if let Some(Bump::Stored(e)) = &account.constraints.bump {
+            referenced_args(e, &mut used);
+        }
+        for key in [&account.constraints.address, &account.constraints.owner]
+            .into_iter()
+            .flatten()
+        {
+            referenced_args(key, &mut used);
+        }
+        if let Some(decimals) = account
+            .constraints
+            .mint
+            .as_ref()
+            .and_then(|m| m.decimals.as_ref())
+        {
+            referenced_args(decimals, &mut used);
+        }
+        for constraint in account.constraints.constraint.iter() {
+            referenced_args(&constraint.cond, &mut used);
+        }
+        if let Some(realloc) = &account.constraints.realloc {
+            referenced_args(&realloc.space, &mut used);
+        }
+    }
+    let count = ix
+        .args
+        .iter()
+        .rposition(|a| used.contains(&a.name))
+        .map_or(0, |i| i + 1);
+    if count == 0 {
+        return Ok(quote! {});
+    }
+    let mut args: Vec<TokenStream> = vec![];
+    for a in ix.args[..count].iter() {
+        let name = ident(&a.name);
+        let of_type = arg_type(a)?;
+        args.push(quote! { #name: #of_type });
+    }
+    Ok(quote! { #[instruction(#(#args),*)] })
+}
+
+fn arg_type(arg: &InstructionArgument) -> Result<TokenStream> {
+    let of_type = rs_type_from_str(&arg.of_type)?;
+    match arg.optional {
+        true => Ok(quote!(Option<#of_type>)),
+        false => Ok(of_type),
+    }
+}
+
+fn referenced_args(e: &Expression, used: &mut Vec<String>) {
+    match e {
+        Expression::Arg(name) => used.push(name.clone()),
+        Expression::Binary { left, right, .. } | Expression::Arithmetic { left, right, .. } => {
+            referenced_args(left, used);
+            referenced_args(right, used);
+        }
+        Expression::Unary { expr: e, .. }
+        | Expression::Cast { expr: e, .. }
+        | Expression::ToBytes(e)
+        | Expression::Clone(e) => referenced_args(e, used),
+        _ => {}
+    }
+}
+
+fn struct_fields(fields: &[ProgramAccountField]) -> Result<Vec<TokenStream>> {
+    fields
+        .iter()
+        .map(|field| {
+            let field_name = ident(&field.name);
+            let field_type = rs_type_from_str(&field.of_type)?;
+            Ok(quote! { pub #field_name: #field_type })
+        })
+        .collect()
+}
+
+/// Enums are `Copy` so they can be compared and assigned like integers.
+fn program_enum(e: &ProgramEnum) -> TokenStream {
+    let enum_name = ident(&e.name);
+    let variants = e.variants.iter().map(|v| ident(v));
+    quote! {
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+        pub enum #enum_name {
+            #(#variants),*
+        }
+    }
+}
+
+/// Structs derive `InitSpace`, with the lengths of their `String`s and `Vec`s in
+/// `#[max_len]`.
+fn program_struct(s: &ProgramStruct) -> Result<TokenStream> {
+    let struct_name = ident(&s.name);
+    let fields = s
+        .fields
+        .iter()
+        .map(|field| {
+            let field_name = ident(&field.name);
+            let field_type = rs_type_from_str(&field.of_type)?;
+            if field.max_len.is_empty() {
+                return Ok(quote! { pub #field_name: #field_type });
+            }
+            let max_len = field.max_len.iter().map(|l| Literal::u32_unsuffixed(*l));
+            Ok(quote! {
+                #[max_len(#(#max_len),*)]
+                pub #field_name: #field_type
+            })
+        })
+        .collect::<Result<Vec<TokenStream>>>()?;
+
+    Ok(quote! {
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+        pub struct #struct_name {
+            #(#fields),*
+        }
+    })
+}
+
+fn handler_params(ix: &ProgramInstruction) -> Result<Vec<TokenStream>> {
+    ix.args
+        .iter()
+        .map(|a| {
+            let name = ident(&a.name);
+            let of_type = arg_type(a)?;
+            Ok(quote! { #name: #of_type })
+        })
+        .collect()
+}
+
+/// `#[constant]` puts the constant in the IDL as well.
+fn constant(c: &ProgramConstant) -> Result<TokenStream> {
+    let name = ident(&c.name);
+    Ok(match &c.value {
+        ConstantValue::Str(s) => quote! {
+            #[constant]
+            pub const #name: &str = #s;
+        },
+        ConstantValue::Bool(b) => quote! {
+            #[constant]
+            pub const #name: bool = #b;
+        },
+        ConstantValue::Pubkey(address) => {
+            // `pubkey!` expands to paths into `solana_program`, which Anchor crates don't
+            // depend on directly. Addresses were validated while lowering.
+            let bytes = Pubkey::from_str(address)
+                .unwrap_or_default()
+                .to_bytes()
+                .map(Literal::u8_unsuffixed);
+            let doc = format!(" {}", address);
+            quote! {
+                #[doc = #doc]
+                #[constant]
+                pub const #name: Pubkey = Pubkey::new_from_array([#(#bytes),*]);
+            }
+        }
+        ConstantValue::Int(i) => {
+            let of_type = rs_type_from_str(&c.of_type)?;
+            let value = Literal::i128_unsuffixed(*i);
+            quote! {
+                #[constant]
+                pub const #name: #of_type = #value;
+            }
+        }
+    })
+}
+
+fn error_code(errors: &[ProgramError]) -> TokenStream {
+    if errors.is_empty() {
+        return quote! {};
+    }
+    let variants = errors.iter().map(|e| {
+        let name = ident(&e.name);
+        match &e.msg {
+            Some(msg) => quote! { #[msg(#msg)] #name },
+            None => quote! { #name },
+        }
+    });
+    quote! {
+        #[error_code]
+        pub enum ErrorCode {
+            #(#variants),*
+        }
+    }
+}
+
+fn seed(s: &Seed, scope: Scope) -> Result<TokenStream> {
+    Ok(match (s, scope) {
+        (Seed::Str(s), _) => {
+            let lit = Literal::byte_string(s.as_bytes());
+            quote!(#lit)
+        }
+        (Seed::AccountKey(account), Scope::Constraint) => {
+            let account = ident(account);
+            quote!(#account.key().as_ref())
+        }
+        (Seed::AccountKey(account), Scope::Handler(_)) => {
+            let account = account_path(account, scope);
+            quote!(#account.to_account_info().key.as_ref())
+        }
+        (Seed::Bytes(e), Scope::Constraint) => {
+            let e = expression(e, scope)?;
+            quote!(#e.to_le_bytes().as_ref())
+        }
+        (Seed::Bytes(e), Scope::Handler(_)) => {
+            let e = expression(e, scope)?;
+            quote!(&#e.to_le_bytes()[..])
+        }
+        (Seed::Bump(e), _) => {
+            let e = expression(e, scope)?;
+            quote!(&[#e])
+        }
+    })
+}
+
+fn expression(e: &Expression, scope: Scope) -> Result<TokenStream> {
+    Ok(match e {
+        Expression::Bool(b) => quote!(#b),
+        Expression::Int(i) => {
+            let lit = Literal::i128_unsuffixed(*i);
+            quote!(#lit)
+        }
+        Expression::Arg(name) | Expression::Local(name) | Expression::Const(name) => {
+            let name = ident(name);
+            quote!(#name)
+        }
+        Expression::Variant { of_type, variant } => {
+            let of_type = ident(of_type);
+            let variant = ident(variant);
+            quote!(#of_type::#variant)
+        }
+        Expression::AccountKey(account) => {
+            let account = account_path(account, scope);
+            quote!(#account.key())
+        }
+        Expression::AccountField { account, field } => {
+            let account = account_path(account, scope);
+            let field = ident(field);
+            quote!(#account.#field)
+        }
+        Expression::Bump(account) => {
+            let account = ident(account);
+            quote!(ctx.bumps.#account)
+        }
+        Expression::Binary { op, left, right } => {
+            let left = operand(left, scope)?;
+            let right = operand(right, scope)?;
+            let op = match op {
+                BinaryOp::Add => quote!(+),
+                BinaryOp::Sub => quote!(-),
+                BinaryOp::Mul => quote!(*),
+                BinaryOp::Div => quote!(/),
+                BinaryOp::Rem => quote!(%),
+                BinaryOp::Shl => quote!(<<),
+                BinaryOp::Shr => quote!(>>),
+                BinaryOp::BitAnd => quote!(&),
+                BinaryOp::BitOr => quote!(|),
+                BinaryOp::BitXor => quote!(^),
+                BinaryOp::Eq => quote!(==),
+                BinaryOp::Ne => quote!(!=),
+                BinaryOp::Lt => quote!(<),
+                BinaryOp::Le => quote!(<=),
+                BinaryOp::Gt => quote!(>),
+                BinaryOp::Ge => quote!(>=),
+                BinaryOp::And => quote!(&&),
+                BinaryOp::Or => quote!(||),
+            };
+            quote!(#left #op #right)
+        }
+        Expression::Arithmetic {
+            op,
+            mode,
+            left,
+            right,
+        } => {
+            let left = operand(left, scope)?;
+            let right = expression(right, scope)?;
+            let op = match op {
+                BinaryOp::Add => "add",
+                BinaryOp::Sub => "sub",
+                BinaryOp::Mul => "mul",
+                BinaryOp::Div => "div",
+                _ => "rem",
+            };
+            match mode {
+                ArithmeticMode::Checked => {
+                    let method = ident(&format!("checked_{}", op));
+                    quote!(#left.#method(#right).ok_or(ErrorCode::Overflow)?)
+                }
+                ArithmeticMode::Saturating => {
+                    let method = ident(&format!("saturating_{}", op));
+                    quote!(#left.#method(#right))
+                }
+                ArithmeticMode::Wrapping => {
+                    let method = ident(&format!("wrapping_{}", op));
+                    quote!(#left.#method(#right))
+                }
+            }
+        }
+        Expression::Unary { op, expr } => {
+            let expr = operand(expr, scope)?;
+            match op {
+                UnaryOp::Neg => quote!(-#expr),
+                UnaryOp::Not => quote!(!#expr),
+            }
+        }
+        Expression::Cast { expr, to } => {
+            if let Expression::Int(i) = **expr {
+                // A typed literal like `1u64`
+                let lit: Literal = format!("{}{}", i, to)
+                    .parse()
+                    .map_err(|_| anyhow!("`{}` is not a valid `{}` literal", i, to))?;
+                return Ok(quote!(#lit));
+            }
+            let expr = operand(expr, scope)?;
+            // Casts only happen between integer types, which map one to one
+            let to = ident(to);
+            quote!(#expr as #to)
+        }
+        Expression::ToBytes(e) => {
+            let e = operand(e, scope)?;
+            quote!(#e.to_le_bytes())
+        }
+        Expression::Clone(e) => {
+            let e = operand(e, scope)?;
+            quote!(#e.clone())
+        }
+    })
+}
+
+/// Parenthesizes nested operations so the TypeScript evaluation order is kept, and
+/// negative literals, which would otherwise negate the method called on them.
+fn operand(e: &Expression, scope: Scope) -> Result<TokenStream> {
+    let tokens = expression(e, scope)?;
+    Ok(match e {
+        Expression::Int(i) if *i < 0 => quote!((#tokens)),
+        Expression::Cast { expr, .. } => match **expr {
+            Expression::Int(i) if i >= 0 => tokens,
+            _ => quote!((#tokens)),
+        },
+        Expression::Binary { .. } | Expression::Unary { .. } => quote!((#tokens)),
+        _ => tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate;
+    use crate::{
+        ir::{Expression, ProgramInstruction, ProgramModule, Statement},
+        transpiler::tests::{program, transpile_source},
+    };
+
+    #[test]
+    fn unknown_types_fail_generation() {
+        let mut program = ProgramModule::new();
+        let mut ix = ProgramInstruction::new("initialize".to_string());
+        ix.body.push(Statement::Let {
+            name: "count".to_string(),
+            mutable: false,
+            of_type: Some("number".to_string()),
+            value: Expression::Int(1),
+        });
+        program.instructions.push(ix);
+        let error = generate(&program).unwrap_err();
+        assert!(error.to_string().contains("number"), "{}", error);
+    }
+
+    #[test]
+    fn constraints_compare_with_operators() {
+        let rust = transpile_source(&program(
+            "check(owner: Signer, vault: Vault, amount: u64): Result {
+                vault
+                    .derive([\"vault\", owner.key])
+                    .constraint(vault.owner.eq(owner.key))
+                    .constraint(vault.amount === amount);
+            }",
+            "export interface Vault extends Account { owner: Pubkey; amount: u64 }",
+        ));
+        assert!(
+            rust.contains("constraint = vault.owner == owner.key(),"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("constraint = vault.amount == amount,"));
+    }
+
+    #[test]
+    fn optional_args_that_are_not_copy_are_cloned_where_moved() {
+        let rust = transpile_source(&program(
+            "rename(owner: Signer, pool: Pool, memo?: Str<32>, tags?: Vec<u8, 4>): Result {
+                pool.derive([\"pool\", owner.key]);
+                if (memo) {
+                    pool.name = memo;
+                    const name = memo;
+                    emit(new Renamed({ memo }));
+                }
+                if (memo) {
+                    pool.name = memo;
+                }
+                if (tags) {
+                    pool.tags = tags;
+                }
+            }",
+            "export interface Pool extends Account { name: Str<32>; tags: Vec<u8, 4> }
+            export interface Renamed extends Event { memo: Str<32> }",
+        ));
+        assert!(rust.contains("if let Some(memo) = &memo {"), "{}", rust);
+        assert!(rust.contains("ctx.accounts.pool.name = memo.clone();"));
+        assert!(rust.contains("let name = memo.clone();"));
+        assert!(rust.contains("emit!(Renamed { memo : memo.clone() });"));
+        assert!(rust.contains("ctx.accounts.pool.tags = tags.clone();"));
+    }
+
+    #[test]
+    fn values_that_are_not_copy_are_cloned_where_moved() {
+        let rust = transpile_source(&program(
+            "rename(owner: Signer, pool: Pool, backup: Backup, name: Str<32>, size: u8): Result {
+                pool.derive([\"pool\", owner.key]);
+                backup.derive([\"backup\", owner.key]);
+                pool.name = name;
+                backup.name = name;
+                backup.previous = pool.name;
+                pool.size = size;
+            }",
+            "export interface Pool extends Account { name: Str<32>; size: u8 }
+            export interface Backup extends Account { name: Str<32>; previous: Str<32> }",
+        ));
+        assert!(
+            rust.contains("ctx.accounts.pool.name = name.clone();"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("ctx.accounts.backup.name = name.clone();"));
+        assert!(rust.contains("ctx.accounts.backup.previous = ctx.accounts.pool.name.clone();"));
+        assert!(rust.contains("ctx.accounts.pool.size = size;"));
+    }
+
+    #[test]
+    fn locals_clone_values_that_are_not_copy() {
+        let rust = transpile_source(&program(
+            "rename(owner: Signer, pool: Pool, name: Str<32>): Result {
+                pool.derive([\"pool\", owner.key]);
+                const previous = pool.name;
+                const size = pool.size;
+                pool.name = name;
+                emit(new Renamed({ previous, name }));
+            }",
+            "export interface Pool extends Account { name: Str<32>; size: u8 }
+            export interface Renamed extends Event { previous: Str<32>; name: Str<32> }",
+        ));
+        assert!(
+            rust.contains("let previous = ctx.accounts.pool.name.clone();"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("let size = ctx.accounts.pool.size;"));
+        assert!(
+            rust.contains("emit!(Renamed { previous : previous.clone(), name : name.clone() });")
+        );
+    }
+
+    #[test]
+    fn negative_literals_are_parenthesized_as_receivers() {
+        let rust = transpile_source(&program(
+            "shift(owner: Signer, pool: Pool, delta: i64): Result {
+                pool.derive([\"pool\", owner.key]);
+                pool.low = -5 + delta;
+                pool.high = delta - -5;
+            }",
+            "export interface Pool extends Account { low: i64; high: i64 }",
+        ));
+        assert!(
+            rust.contains("ctx.accounts.pool.low = (-5i64).checked_add(delta)"),
+            "{}",
+            rust
+        );
+        assert!(
+            rust.contains("ctx.accounts.pool.high = delta.checked_sub(-5)"),
+            "{}",
+            rust
+        );
+    }
+}
@@ -0,0 +1,746 @@
+//! Generates a typed TypeScript client of a program from the `ir`: instruction builders,
+//! PDA helpers and account decoders on top of `@solana/web3.js`.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use anyhow::Result;
+use convert_case::{Case, Casing};
+
+use crate::{
+    codegen::idl::discriminator,
+    ir::{
+        AccountType, BuiltinProgram, ConstantValue, Expression, InstructionAccount, ProgramAccount,
+        ProgramAccountField, ProgramConstant, ProgramEnum, ProgramInstruction, ProgramModule,
+        ProgramStruct, Seed,
+    },
+    ts_types::PoseidonType,
+};
+
+/// Borsh encoding of the few types Poseidon has, so the client needs nothing but web3.js.
+const PRELUDE: &str = r#"class Writer {
+  private buf: number[] = [];
+
+  private int(value: bigint, bytes: number): this {
+    let v = BigInt.asUintN(bytes * 8, value);
+    for (let i = 0; i < bytes; i++) {
+      this.buf.push(Number(v & 0xffn));
+      v >>= 8n;
+    }
+    return this;
+  }
+
+  u8(v: number) { return this.int(BigInt(v), 1); }
+  i8(v: number) { return this.int(BigInt(v), 1); }
+  u16(v: number) { return this.int(BigInt(v), 2); }
+  i16(v: number) { return this.int(BigInt(v), 2); }
+  u32(v: number) { return this.int(BigInt(v), 4); }
+  i32(v: number) { return this.int(BigInt(v), 4); }
+  u64(v: bigint) { return this.int(v, 8); }
+  i64(v: bigint) { return this.int(v, 8); }
+  u128(v: bigint) { return this.int(v, 16); }
+  i128(v: bigint) { return this.int(v, 16); }
+  bool(v: boolean) { return this.u8(v ? 1 : 0); }
+
+  raw(v: Uint8Array): this {
+    this.buf.push(...v);
+    return this;
+  }
+
+  bytes(v: Uint8Array) { return this.u32(v.length).raw(v); }
+  string(v: string) { return this.bytes(new TextEncoder().encode(v)); }
+  pubkey(v: PublicKey) { return this.raw(v.toBytes()); }
+
+  vec<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    return this.u32(items.length).array(items, item);
+  }
+
+  array<T>(items: T[], item: (w: Writer, v: T) => void): this {
+    items.forEach((v) => item(this, v));
+    return this;
+  }
+
+  option<T>(value: T | null | undefined, item: (w: Writer, v: T) => void): this {
+    if (value === null || value === undefined) {
+      return this.u8(0);
+    }
+    item(this.u8(1), value);
+    return this;
+  }
+
+  toBuffer(): Buffer {
+    return Buffer.from(this.buf);
+  }
+}
+
+class Reader {
+  private offset = 0;
+
+  constructor(private data: Uint8Array) {}
+
+  private int(bytes: number, signed: boolean): bigint {
+    let v = 0n;
+    for (let i = bytes - 1; i >= 0; i--) {
+      v = (v << 8n) | BigInt(this.data[this.offset + i]);
+    }
+    this.offset += bytes;
+    return signed ? BigInt.asIntN(bytes * 8, v) : v;
+  }
+
+  u8() { return Number(this.int(1, false)); }
+  i8() { return Number(this.int(1, true)); }
+  u16() { return Number(this.int(2, false)); }
+  i16() { return Number(this.int(2, true)); }
+  u32() { return Number(this.int(4, false)); }
+  i32() { return Number(this.int(4, true)); }
+  u64() { return this.int(8, false); }
+  i64() { return this.int(8, true); }
+  u128() { return this.int(16, false); }
+  i128() { return this.int(16, true); }
+  bool() { return this.u8() !== 0; }
+
+  raw(length: number): Uint8Array {
+    const v = this.data.slice(this.offset, this.offset + length);
+    this.offset += length;
+    return v;
+  }
+
+  bytes() { return this.raw(this.u32()); }
+  string() { return new TextDecoder().decode(this.bytes()); }
+  pubkey() { return new PublicKey(this.raw(32)); }
+
+  vec<T>(item: (r: Reader) => T): T[] {
+    return this.array(this.u32(), item);
+  }
+
+  array<T>(length: number, item: (r: Reader) => T): T[] {
+    return Array.from({ length }, () => item(this));
+  }
+
+  option<T>(item: (r: Reader) => T): T | null {
+    return this.u8() === 0 ? null : item(this);
+  }
+}
+
+function checkDiscriminator(data: Uint8Array, discriminator: Uint8Array, name: string) {
+  if (!discriminator.every((b, i) => data[i] === b)) {
+    throw new Error(`Account is not a ${name}`);
+  }
+}
+"#;
+
+pub fn generate(program: &ProgramModule) -> Result<String> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Generated by Poseidon from the `{}` program.",
+        program.name
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "import {{ PublicKey, TransactionInstruction }} from \"@solana/web3.js\";"
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "export const PROGRAM_ID = new PublicKey(\"{}\");",
+        program.id
+    )?;
+    for c in program.constants.iter() {
+        writeln!(out, "export const {} = {};", c.name, constant_value(c)?)?;
+    }
+    for p in program.external_programs() {
+        writeln!(
+            out,
+            "const {}_ID = new PublicKey(\"{}\");",
+            p.account_name().to_case(Case::UpperSnake),
+            p.address
+        )?;
+    }
+    for p in builtin_programs(program) {
+        if let Some(address) = p.address() {
+            writeln!(
+                out,
+                "const {} = new PublicKey(\"{}\");",
+                program_const(p),
+                address
+            )?;
+        }
+    }
+    writeln!(out)?;
+    out.push_str(PRELUDE);
+    for e in program.enums.iter() {
+        writeln!(out)?;
+        enum_codec(&mut out, e)?;
+    }
+    for s in program.structs.iter() {
+        writeln!(out)?;
+        struct_codec(&mut out, s)?;
+    }
+    for account in program.accounts.iter() {
+        writeln!(out)?;
+        decoder(&mut out, account)?;
+    }
+    let pdas = pda_helpers(program)?;
+    for helper in pdas.helpers.iter() {
+        writeln!(out)?;
+        pda_helper(&mut out, helper)?;
+    }
+    for ix in program.instructions.iter() {
+        writeln!(out)?;
+        builder(&mut out, ix, &pdas)?;
+    }
+    Ok(out)
+}
+
+/// The builtin programs any instruction passes, each declared once.
+fn builtin_programs(program: &ProgramModule) -> Vec<BuiltinProgram> {
+    let mut programs: Vec<BuiltinProgram> = vec![];
+    for p in program.instructions.iter().flat_map(|ix| ix.programs()) {
+        if !programs.contains(&p) {
+            programs.push(p);
+        }
+    }
+    programs
+}
+
+fn program_const(p: BuiltinProgram) -> String {
+    format!("{}_ID", p.account_name().to_case(Case::UpperSnake))
+}
+
+fn constant_value(c: &ProgramConstant) -> Result<String> {
+    Ok(match &c.value {
+        ConstantValue::Int(i) if ts_type(&c.of_type)? == "bigint" => format!("{}n", i),
+        ConstantValue::Int(i) => i.to_string(),
+        ConstantValue::Bool(b) => b.to_string(),
+        ConstantValue::Str(s) => js_string(s),
+        ConstantValue::Pubkey(address) => format!("new PublicKey(\"{}\")", address),
+    })
+}
+
+/// A JS string literal of `s`. JSON strings are JS strings, unlike Rust's debug output
+/// which escapes characters like `\u{1f600}`.
+fn js_string(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+fn bytes(data: &[u8]) -> String {
+    let data: Vec<String> = data.iter().map(|b| b.to_string()).collect();
+    format!("new Uint8Array([{}])", data.join(", "))
+}
+
+fn interface(out: &mut String, name: &str, fields: &[ProgramAccountField]) -> Result<()> {
+    writeln!(out, "export interface {} {{", name)?;
+    for f in fields.iter() {
+        writeln!(
+            out,
+            "  {}: {};",
+            f.name.to_case(Case::Camel),
+            ts_type(&f.of_type)?
+        )?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// A numeric TS enum, whose values are the Borsh variant indices.
+fn enum_codec(out: &mut String, e: &ProgramEnum) -> Result<()> {
+    let name = &e.name;
+    writeln!(out, "export enum {} {{", name)?;
+    for v in e.variants.iter() {
+        writeln!(out, "  {},", v)?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "function write{}(w: Writer, v: {}) {{", name, name)?;
+    writeln!(out, "  w.u8(v);")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "function read{}(r: Reader): {} {{", name, name)?;
+    writeln!(out, "  return r.u8();")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// The interface of a struct and the functions the other codecs call to encode it.
+fn struct_codec(out: &mut String, s: &ProgramStruct) -> Result<()> {
+    let name = &s.name;
+    interface(out, name, &s.fields)?;
+    writeln!(out)?;
+    writeln!(out, "function write{}(w: Writer, v: {}) {{", name, name)?;
+    for f in s.fields.iter() {
+        let value = format!("v.{}", f.name.to_case(Case::Camel));
+        writeln!(out, "  {};", write_value(&f.of_type, &value)?)?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "function read{}(r: Reader): {} {{", name, name)?;
+    writeln!(out, "  return {{")?;
+    for f in s.fields.iter() {
+        writeln!(
+            out,
+            "    {}: {},",
+            f.name.to_case(Case::Camel),
+            read(&f.of_type)?
+        )?;
+    }
+    writeln!(out, "  }};")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+fn decoder(out: &mut String, account: &ProgramAccount) -> Result<()> {
+    let name = &account.name;
+    let discriminator_const = format!("{}_DISCRIMINATOR", name.to_case(Case::UpperSnake));
+    interface(out, name, &account.fields)?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "export const {} = {};",
+        discriminator_const,
+        bytes(&discriminator("account", name))
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "export function decode{}(data: Uint8Array): {} {{",
+        name, name
+    )?;
+    writeln!(
+        out,
+        "  checkDiscriminator(data, {}, \"{}\");",
+        discriminator_const, name
+    )?;
+    writeln!(out, "  const r = new Reader(data.subarray(8));")?;
+    writeln!(out, "  return {{")?;
+    for f in account.fields.iter() {
+        writeln!(
+            out,
+            "    {}: {},",
+            f.name.to_case(Case::Camel),
+            read(&f.of_type)?
+        )?;
+    }
+    writeln!(out, "  }};")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// A `find...Address` function deriving an account from its `derive([...])` seeds.
+struct PdaHelper {
+    name: String,
+    /// The values the seeds are made of, as `(parameter, TS type)`.
+    params: Vec<(String, String)>,
+    seeds: Vec<String>,
+}
+
+#[derive(Default)]
+struct PdaHelpers {
+    helpers: Vec<PdaHelper>,
+    /// The helper of each `(instruction, account)`.
+    accounts: BTreeMap<(String, String), usize>,
+}
+
+impl PdaHelpers {
+    fn get(&self, ix: &ProgramInstruction, account: &InstructionAccount) -> Option<&PdaHelper> {
+        let i = self
+            .accounts
+            .get(&(ix.name.clone(), account.name.clone()))?;
+        Some(&self.helpers[*i])
+    }
+}
+
+/// The PDA helpers of every derived account. Accounts derived the same way in several
+/// instructions share a helper; ones derived differently get one per instruction.
+fn pda_helpers(program: &ProgramModule) -> Result<PdaHelpers> {
+    let mut found = vec![];
+    for ix in program.instructions.iter() {
+        for account in ix.accounts.iter() {
+            let Some(seeds) = &account.constraints.seeds else {
+                continue;
+            };
+            if let Some((params, seeds)) = pda_seeds(program, ix, seeds)? {
+                found.push((ix, account, params, seeds));
+            }
+        }
+    }
+    let mut pdas = PdaHelpers::default();
+    for (ix, account, params, seeds) in found.iter() {
+        let conflicts = found
+            .iter()
+            .any(|(_, a, _, s)| a.name == account.name && s != seeds);
+        let name = match conflicts {
+            true => format!("{}_{}", ix.name, account.name),
+            false => account.name.clone(),
+        };
+        let name = format!("find{}Address", name.to_case(Case::Pascal));
+        let i = match pdas.helpers.iter().position(|h| h.name == name) {
+            Some(i) => i,
+            None => {
+                pdas.helpers.push(PdaHelper {
+                    name,
+                    params: params.clone(),
+                    seeds: seeds.clone(),
+                });
+                pdas.helpers.len() - 1
+            }
+        };
+        pdas.accounts
+            .insert((ix.name.clone(), account.name.clone()), i);
+    }
+    Ok(pdas)
+}
+
+fn pda_helper(out: &mut String, helper: &PdaHelper) -> Result<()> {
+    let mut params: Vec<String> = helper
+        .params
+        .iter()
+        .map(|(p, t)| format!("{}: {}", p, t))
+        .collect();
+    params.push(String::from("programId = PROGRAM_ID"));
+    writeln!(
+        out,
+        "export function {}({}): [PublicKey, number] {{",
+        helper.name,
+        params.join(", ")
+    )?;
+    writeln!(
+        out,
+        "  return PublicKey.findProgramAddressSync([{}], programId);",
+        helper.seeds.join(", ")
+    )?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// The parameters and TS expressions of PDA seeds, or nothing if a seed can't be computed
+/// off-chain from plain values.
+#[allow(clippy::type_complexity)]
+fn pda_seeds(
+    program: &ProgramModule,
+    ix: &ProgramInstruction,
+    seeds: &[Seed],
+) -> Result<Option<(Vec<(String, String)>, Vec<String>)>> {
+    let mut params: Vec<(String, String)> = vec![];
+    let mut exprs = vec![];
+    for seed in seeds {
+        let (param, of_type) = match seed {
+            Seed::Str(s) => {
+                exprs.push(format!("Buffer.from({})", js_string(s)));
+                continue;
+            }
+            Seed::AccountKey(account) => (account.to_case(Case::Camel), "Pubkey".to_string()),
+            Seed::Bytes(Expression::Arg(name)) => match ix.arg(name) {
+                Some(arg) => (name.to_case(Case::Camel), arg.of_type.clone()),
+                None => return Ok(None),
+            },
+            Seed::Bytes(Expression::AccountField { account, field }) => {
+                let of_type = match ix.account(account).map(|a| &a.of_type) {
+                    Some(AccountType::Custom(ty)) => program
+                        .account(ty)
+                        .and_then(|a| a.fields.iter().find(|f| f.name == *field))
+                        .map(|f| f.of_type.clone()),
+                    _ => None,
+                };
+                // Named after the field, so an account stored with an argument it's derived
+                // from is derived with the same helper when it's read back
+                match of_type {
+                    Some(of_type) => (field.to_case(Case::Camel), of_type),
+                    None => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+        let expr = match of_type.as_str() {
+            "Pubkey" => format!("{}.toBuffer()", param),
+            // Integers are seeded with their little-endian bytes
+            "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128" => {
+                format!("new Writer().{}({}).toBuffer()", of_type, param)
+            }
+            _ => return Ok(None),
+        };
+        exprs.push(expr);
+        if !params.iter().any(|(p, _)| *p == param) {
+            params.push((param, ts_type(&of_type)?));
+        }
+    }
+    Ok(Some((params, exprs)))
+}
+
+fn builder(out: &mut String, ix: &ProgramInstruction, pdas: &PdaHelpers) -> Result<()> {
+    let name = ix.name.to_case(Case::Camel);
+    let pascal = ix.name.to_case(Case::Pascal);
+    writeln!(out, "export interface {}Accounts {{", pascal)?;
+    for a in ix.accounts.iter() {
+        let doc = pdas
+            .get(ix, a)
+            .map(|h| {
+                let params: Vec<&str> = h.params.iter().map(|(p, _)| p.as_str()).collect();
+                format!("  /** `{}({})[0]` */\n", h.name, params.join(", "))
+            })
+            .unwrap_or_default();
+        write!(out, "{}", doc)?;
+        match a.optional {
+            true => writeln!(out, "  {}?: PublicKey | null;", a.name.to_case(Case::Camel))?,
+            false => writeln!(out, "  {}: PublicKey;", a.name.to_case(Case::Camel))?,
+        }
+    }
+    // Programs the caller picks, like the token program of Token-2022 accounts
+    for p in ix.programs().iter().filter(|p| p.address().is_none()) {
+        writeln!(
+            out,
+            "  {}: PublicKey;",
+            p.account_name().to_case(Case::Camel)
+        )?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    let has_args = !ix.args.is_empty();
+    if has_args {
+        writeln!(out, "export interface {}Args {{", pascal)?;
+        for arg in ix.args.iter() {
+            let of_type = ts_type(&arg.of_type)?;
+            match arg.optional {
+                true => writeln!(
+                    out,
+                    "  {}?: {} | null;",
+                    arg.name.to_case(Case::Camel),
+                    of_type
+                )?,
+                false => writeln!(out, "  {}: {};", arg.name.to_case(Case::Camel), of_type)?,
+            }
+        }
+        writeln!(out, "}}")?;
+        writeln!(out)?;
+    }
+    writeln!(
+        out,
+        "export function {}(\n  accounts: {}Accounts,{}\n  programId = PROGRAM_ID,\n): TransactionInstruction {{",
+        name,
+        pascal,
+        match has_args {
+            true => format!("\n  args: {}Args,", pascal),
+            false => String::new(),
+        }
+    )?;
+    writeln!(
+        out,
+        "  const w = new Writer().raw({});",
+        bytes(&discriminator("global", &ix.name))
+    )?;
+    // Borsh lays the arguments out in the order the handler takes them
+    for arg in ix.args.iter() {
+        let value = format!("args.{}", arg.name.to_case(Case::Camel));
+        match arg.optional {
+            true => writeln!(
+                out,
+                "  w.option({}, (w, v) => {});",
+                value,
+                write_value(&arg.of_type, "v")?
+            )?,
+            false => writeln!(out, "  {};", write_value(&arg.of_type, &value)?)?,
+        }
+    }
+    writeln!(out, "  return new TransactionInstruction({{")?;
+    writeln!(out, "    programId,")?;
+    writeln!(out, "    keys: [")?;
+    for a in ix.accounts.iter() {
+        let key = format!("accounts.{}", a.name.to_case(Case::Camel));
+        let meta = format!(
+            "{{ pubkey: {}, isSigner: {}, isWritable: {} }}",
+            key,
+            a.is_signer(),
+            a.is_writable()
+        );
+        match a.optional {
+            // Anchor reads the program id in place of a missing optional account
+            true => writeln!(
+                out,
+                "      {} ? {} : {{ pubkey: programId, isSigner: false, isWritable: false }},",
+                key, meta
+            )?,
+            false => writeln!(out, "      {},", meta)?,
+        }
+    }
+    for p in ix.external_programs.iter() {
+        writeln!(
+            out,
+            "      {{ pubkey: {}_ID, isSigner: false, isWritable: false }},",
+            p.account_name().to_case(Case::UpperSnake)
+        )?;
+    }
+    for p in ix.programs() {
+        let key = match p.address() {
+            Some(_) => program_const(p),
+            None => format!("accounts.{}", p.account_name().to_case(Case::Camel)),
+        };
+        writeln!(
+            out,
+            "      {{ pubkey: {}, isSigner: false, isWritable: false }},",
+            key
+        )?;
+    }
+    writeln!(out, "    ],")?;
+    writeln!(out, "    data: w.toBuffer(),")?;
+    writeln!(out, "  }});")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+fn ts_type(of_type: &str) -> Result<String> {
+    Ok(parsed_ts_type(&PoseidonType::parse(of_type)?))
+}
+
+fn parsed_ts_type(ty: &PoseidonType) -> String {
+    match ty {
+        PoseidonType::Scalar(name) => match *name {
+            "u8" | "i8" | "u16" | "i16" | "u32" | "i32" => String::from("number"),
+            "Boolean" => String::from("boolean"),
+            "Pubkey" => String::from("PublicKey"),
+            _ => String::from("bigint"),
+        },
+        PoseidonType::Str => String::from("string"),
+        PoseidonType::Bytes => String::from("Uint8Array"),
+        PoseidonType::Vec(item) | PoseidonType::Array(item, _) if is_u8(item) => {
+            String::from("Uint8Array")
+        }
+        PoseidonType::Vec(item) | PoseidonType::Array(item, _) => match **item {
+            PoseidonType::Option(_) => format!("({})[]", parsed_ts_type(item)),
+            _ => format!("{}[]", parsed_ts_type(item)),
+        },
+        PoseidonType::Option(item) => format!("{} | null", parsed_ts_type(item)),
+        PoseidonType::Defined(name) => name.to_string(),
+    }
+}
+
+/// The `Writer` call appending `value` to `w`.
+fn write_value(of_type: &str, value: &str) -> Result<String> {
+    Ok(write_parsed(&PoseidonType::parse(of_type)?, value))
+}
+
+fn write_parsed(ty: &PoseidonType, value: &str) -> String {
+    match ty {
+        PoseidonType::Scalar(name) => format!("w.{}({})", method(name), value),
+        PoseidonType::Str => format!("w.string({})", value),
+        PoseidonType::Bytes => format!("w.bytes({})", value),
+        PoseidonType::Vec(item) if is_u8(item) => format!("w.bytes({})", value),
+        PoseidonType::Vec(item) => {
+            format!("w.vec({}, (w, v) => {})", value, write_parsed(item, "v"))
+        }
+        // Arrays have no length prefix
+        PoseidonType::Array(item, _) if is_u8(item) => format!("w.raw({})", value),
+        PoseidonType::Array(item, _) => {
+            format!("w.array({}, (w, v) => {})", value, write_parsed(item, "v"))
+        }
+        PoseidonType::Option(item) => {
+            format!("w.option({}, (w, v) => {})", value, write_parsed(item, "v"))
+        }
+        PoseidonType::Defined(name) => format!("write{}(w, {})", name, value),
+    }
+}
+
+/// The `Reader` call reading the next value off `r`.
+fn read(of_type: &str) -> Result<String> {
+    Ok(read_parsed(&PoseidonType::parse(of_type)?))
+}
+
+fn read_parsed(ty: &PoseidonType) -> String {
+    match ty {
+        PoseidonType::Scalar(name) => format!("r.{}()", method(name)),
+        PoseidonType::Str => String::from("r.string()"),
+        PoseidonType::Bytes => String::from("r.bytes()"),
+        PoseidonType::Vec(item) if is_u8(item) => String::from("r.bytes()"),
+        PoseidonType::Vec(item) => format!("r.vec((r) => {})", read_parsed(item)),
+        PoseidonType::Array(item, len) if is_u8(item) => format!("r.raw({})", len),
+        PoseidonType::Array(item, len) => {
+            format!("r.array({}, (r) => {})", len, read_parsed(item))
+        }
+        PoseidonType::Option(item) => format!("r.option((r) => {})", read_parsed(item)),
+        PoseidonType::Defined(name) => format!("read{}(r)", name),
+    }
+}
+
+/// The `Writer` and `Reader` method of a scalar.
+fn method(name: &str) -> &str {
+    match name {
+        "Boolean" => "bool",
+        "Pubkey" => "pubkey",
+        // Borsh writes them as 64 bit integers
+        "usize" => "u64",
+        "isize" => "i64",
+        _ => name,
+    }
+}
+
+fn is_u8(ty: &PoseidonType) -> bool {
+    *ty == PoseidonType::Scalar("u8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, js_string};
+    use crate::transpiler::tests::{lower_source, program};
+
+    const COUNTER: &str = "export interface Counter extends Account { count: u64; id: u64 }";
+
+    #[test]
+    fn pda_seeds_encode_integers_little_endian() {
+        let client = generate(
+            &lower_source(&program(
+                "initialize(owner: Signer, counter: Counter, id: u64): Result {
+                    counter.derive([\"counter\", owner.key, id.toBytes()]).init(owner);
+                    counter.id = id;
+                }
+                increment(owner: Signer, counter: Counter): Result {
+                    counter.derive([\"counter\", owner.key, counter.id.toBytes()]);
+                    counter.count += 1;
+                }",
+                COUNTER,
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        // The stored id is the same seed as the argument, so both share a helper
+        assert!(
+            client.contains(
+                "export function findCounterAddress(owner: PublicKey, id: bigint, programId = PROGRAM_ID): [PublicKey, number] {\n  \
+                 return PublicKey.findProgramAddressSync([Buffer.from(\"counter\"), owner.toBuffer(), new Writer().u64(id).toBuffer()], programId);"
+            ),
+            "{}",
+            client
+        );
+    }
+
+    #[test]
+    fn conflicting_pdas_get_a_helper_per_instruction() {
+        let client = generate(
+            &lower_source(&program(
+                "initialize(owner: Signer, counter: Counter): Result {
+                    counter.derive([\"counter\", owner.key]).init(owner);
+                }
+                reset(owner: Signer, counter: Counter): Result {
+                    counter.derive([\"other\", owner.key]);
+                    counter.count = 0;
+                }",
+                COUNTER,
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(
+            client.contains("export function findInitializeCounterAddress("),
+            "{}",
+            client
+        );
+        assert!(client.contains("export function findResetCounterAddress("));
+        assert!(!client.contains("export function findCounterAddress("));
+    }
+
+    #[test]
+    fn strings_are_escaped_for_js() {
+        assert_eq!(js_string("vault"), "\"vault\"");
+        assert_eq!(js_string("a \"b\"\n"), "\"a \\\"b\\\"\\n\"");
+        assert_eq!(js_string("\u{1f600}"), "\"\u{1f600}\"");
+        assert_eq!(js_string("\0"), "\"\\u0000\"");
+    }
+}
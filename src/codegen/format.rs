@@ -0,0 +1,228 @@
+//! Formats the Rust generated by `anchor` with prettyplease.
+//!
+//! prettyplease prints the arguments of attributes token by token, following every `=` with a
+//! space, which turns the `==` of a `constraint = a == b` into `= =`. The `#[account(...)]` and
+//! `#[instruction(...)]` attributes of accounts structs are printed here instead, with their
+//! values parsed and formatted as the expressions and types they are.
+
+use anyhow::{anyhow, Result};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::quote;
+use syn::{parse_quote, Attribute, Expr, Fields, File, Item, LitStr, Type};
+
+/// Attributes are kept on one line when they fit within prettyplease's margin.
+const MARGIN: usize = 100;
+const INDENT: &str = "    ";
+
+/// Stands in for an attribute while the rest of the file is formatted, printed as a `///`
+/// line that is then replaced by the attribute at `index`.
+fn placeholder(index: usize) -> String {
+    format!("\0poseidon attribute {}", index)
+}
+
+pub fn format(tokens: TokenStream) -> Result<String> {
+    let mut file: File = syn::parse2(tokens)?;
+    let mut attributes: Vec<Attribute> = vec![];
+    for item in file.items.iter_mut() {
+        let Item::Struct(item) = item else {
+            continue;
+        };
+        take_attributes(&mut item.attrs, "instruction", &mut attributes);
+        if let Fields::Named(fields) = &mut item.fields {
+            for field in fields.named.iter_mut() {
+                take_attributes(&mut field.attrs, "account", &mut attributes);
+            }
+        }
+    }
+
+    let code = prettyplease::unparse(&file);
+    let mut formatted = String::with_capacity(code.len());
+    for line in code.lines() {
+        let content = line.trim_start();
+        let indent = &line[..line.len() - content.len()];
+        let attribute =
+            (0..attributes.len()).find(|i| content == format!("///{}", placeholder(*i)));
+        match attribute {
+            Some(index) => formatted.push_str(&attribute_lines(&attributes[index], indent)?),
+            None => formatted.push_str(line),
+        }
+        formatted.push('\n');
+    }
+    Ok(formatted)
+}
+
+/// Moves the `name` attributes out of `attrs` into `taken`, leaving placeholders behind.
+fn take_attributes(attrs: &mut [Attribute], name: &str, taken: &mut Vec<Attribute>) {
+    for attr in attrs.iter_mut().filter(|a| a.path.is_ident(name)) {
+        let doc = LitStr::new(&placeholder(taken.len()), proc_macro2::Span::call_site());
+        taken.push(std::mem::replace(attr, parse_quote!(#[doc = #doc])));
+    }
+}
+
+/// Prints `attr` starting at `indent`, on one line if it fits and with an argument per line
+/// otherwise.
+fn attribute_lines(attr: &Attribute, indent: &str) -> Result<String> {
+    let name = attr
+        .path
+        .get_ident()
+        .ok_or(anyhow!("expected an attribute name"))?
+        .to_string();
+    let args = match attr.tokens.clone().into_iter().next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            split(group.stream(), ',')
+        }
+        _ => return Ok(format!("{}#[{}]", indent, name)),
+    };
+    let args = args
+        .into_iter()
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| match name.as_str() {
+            "instruction" => instruction_arg(arg),
+            _ => account_arg(arg),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let line = format!("{}#[{}({})]", indent, name, args.join(", "));
+    if !line.contains('\n') && line.len() <= MARGIN {
+        return Ok(line);
+    }
+    let inner = format!("{}{}", indent, INDENT);
+    let args = args
+        .iter()
+        .map(|arg| format!("{}{}", inner, arg.replace('\n', &format!("\n{}", inner))))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    Ok(format!("{}#[{}(\n{}\n{})]", indent, name, args, indent))
+}
+
+/// `mut`, `payer = payer`, `mint::decimals = 6` or `constraint = a == b @ Error::Code`.
+fn account_arg(tokens: Vec<TokenTree>) -> Result<String> {
+    let Some(eq) = tokens.iter().position(|t| is_punct(t, '=')) else {
+        return Ok(path(&tokens));
+    };
+    let mut value = split(tokens[eq + 1..].iter().cloned().collect(), '@').into_iter();
+    let mut arg = format!(
+        "{} = {}",
+        path(&tokens[..eq]),
+        expr(value.next().unwrap_or_default())?
+    );
+    if let Some(error) = value.next() {
+        arg.push_str(&format!(" @ {}", expr(error)?));
+    }
+    Ok(arg)
+}
+
+/// `name: Type`
+fn instruction_arg(tokens: Vec<TokenTree>) -> Result<String> {
+    match tokens.as_slice() {
+        [TokenTree::Ident(name), colon, ty @ ..] if is_punct(colon, ':') => {
+            let ty: Type = syn::parse2(ty.iter().cloned().collect())?;
+            Ok(format!(
+                "{}: {}",
+                name,
+                unparse(parse_quote!(type T = #ty;), "type T = ")?
+            ))
+        }
+        _ => Err(anyhow!(
+            "expected an instruction argument, found `{}`",
+            path(&tokens)
+        )),
+    }
+}
+
+fn expr(tokens: Vec<TokenTree>) -> Result<String> {
+    let expr: Expr = syn::parse2(tokens.into_iter().collect())?;
+    unparse(parse_quote!(const _: () = #expr;), "const _: () = ")
+}
+
+/// Formats `item` alone and strips it down to what follows `prefix`.
+fn unparse(item: Item, prefix: &str) -> Result<String> {
+    let code = prettyplease::unparse(&parse_quote!(#item));
+    code.trim_end()
+        .strip_prefix(prefix)
+        .and_then(|code| code.strip_suffix(';'))
+        .map(String::from)
+        .ok_or(anyhow!("cannot format `{}`", quote!(#item)))
+}
+
+/// Paths and keywords such as `mint::decimals` and `mut`, which are printed without spaces.
+fn path(tokens: &[TokenTree]) -> String {
+    tokens.iter().map(ToString::to_string).collect()
+}
+
+fn is_punct(token: &TokenTree, ch: char) -> bool {
+    matches!(token, TokenTree::Punct(p) if p.as_char() == ch)
+}
+
+/// Splits `tokens` on the `separator` punctuation outside of any group.
+fn split(tokens: TokenStream, separator: char) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![vec![]];
+    for token in tokens {
+        match is_punct(&token, separator) {
+            true => parts.push(vec![]),
+            false => parts.last_mut().unwrap().push(token),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_struct(attrs: TokenStream) -> String {
+        format(quote! {
+            #[derive(Accounts)]
+            pub struct Check<'info> {
+                #attrs
+                pub vault: Account<'info, Vault>,
+            }
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn equality_is_kept_whole() {
+        let code = format_struct(quote! {
+            #[account(constraint = vault.owner == owner.key() @ VaultError::NotTheVaultOwner, mint::decimals = 6)]
+        });
+        assert!(
+            code.contains(
+                "    #[account(\n        constraint = vault.owner == owner.key() @ VaultError::NotTheVaultOwner,\n        mint::decimals = 6\n    )]\n"
+            ),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn strings_and_chars_are_left_alone() {
+        let code = format_struct(quote! {
+            #[account(seeds = [b"= =", &[b'='], r"a = = b".as_bytes()], bump)]
+        });
+        assert!(
+            code.contains(r#"#[account(seeds = [b"= =", &[b'='], r"a = = b".as_bytes()], bump)]"#),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn instruction_arguments_are_spaced() {
+        let code = format(quote! {
+            #[derive(Accounts)]
+            #[instruction(seed: u64, names: Vec<String>)]
+            pub struct Check<'info> {
+                #[account(realloc = 8 + names.len() as usize, realloc::payer = owner, realloc::zero = false)]
+                pub vault: Account<'info, Vault>,
+            }
+        })
+        .unwrap();
+        assert!(code.contains("#[instruction(seed: u64, names: Vec<String>)]\n"));
+        assert!(
+            code.contains("realloc = 8 + names.len() as usize, realloc::payer"),
+            "{}",
+            code
+        );
+    }
+}
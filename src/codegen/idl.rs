@@ -0,0 +1,484 @@
+//! Generates the Anchor 0.30 IDL of a program from the `ir`, without building it.
+
+use std::str::FromStr;
+
+use anchor_lang::{prelude::Pubkey, solana_program::hash::hash};
+use anchor_lang_idl::types::{
+    Idl, IdlAccount, IdlArrayLen, IdlConst, IdlDefinedFields, IdlEnumVariant, IdlErrorCode,
+    IdlEvent, IdlField, IdlInstruction, IdlInstructionAccount, IdlInstructionAccountItem,
+    IdlMetadata, IdlPda, IdlSeed, IdlSeedAccount, IdlSeedArg, IdlSeedConst, IdlType, IdlTypeDef,
+    IdlTypeDefTy, IDL_SPEC,
+};
+use anyhow::Result;
+use convert_case::{Case, Casing};
+
+use crate::{
+    ir::{
+        AccountType, BuiltinProgram, ConstantValue, Expression, InstructionAccount,
+        ProgramAccountField, ProgramEnum, ProgramInstruction, ProgramModule, Seed,
+    },
+    ts_types::PoseidonType,
+};
+
+/// Anchor numbers custom errors from here on.
+const ERROR_CODE_OFFSET: u32 = 6000;
+
+/// Tells the IDLs Poseidon writes apart, when they are imported for CPIs.
+pub const DESCRIPTION: &str = "Created with Poseidon";
+
+pub fn generate(program: &ProgramModule) -> Result<Idl> {
+    let instructions = program
+        .instructions
+        .iter()
+        .map(|ix| instruction(program, ix))
+        .collect::<Result<Vec<_>>>()?;
+    let mut types = vec![];
+    for account in program.accounts.iter() {
+        types.push(type_def(&account.name, &account.fields)?);
+    }
+    for s in program.structs.iter() {
+        types.push(type_def(&s.name, &s.fields)?);
+    }
+    for e in program.enums.iter() {
+        types.push(enum_type_def(e));
+    }
+    for event in program.events.iter() {
+        types.push(type_def(&event.name, &event.fields)?);
+    }
+    Ok(Idl {
+        address: program.id.clone(),
+        metadata: IdlMetadata {
+            name: program.name.to_case(Case::Snake),
+            version: String::from("0.1.0"),
+            spec: IDL_SPEC.to_string(),
+            description: Some(DESCRIPTION.to_string()),
+            repository: None,
+            dependencies: vec![],
+            contact: None,
+            deployments: None,
+        },
+        docs: vec![],
+        instructions,
+        accounts: program
+            .accounts
+            .iter()
+            .map(|a| IdlAccount {
+                name: a.name.clone(),
+                discriminator: discriminator("account", &a.name),
+            })
+            .collect(),
+        events: program
+            .events
+            .iter()
+            .map(|e| IdlEvent {
+                name: e.name.clone(),
+                discriminator: discriminator("event", &e.name),
+            })
+            .collect(),
+        errors: program
+            .errors
+            .iter()
+            .zip(ERROR_CODE_OFFSET..)
+            .map(|(e, code)| IdlErrorCode {
+                code,
+                name: e.name.clone(),
+                msg: e.msg.clone(),
+            })
+            .collect(),
+        types,
+        constants: program
+            .constants
+            .iter()
+            .map(|c| {
+                Ok(IdlConst {
+                    name: c.name.clone(),
+                    docs: vec![],
+                    ty: idl_type(&c.of_type)?,
+                    // Anchor records the Rust expression of the value
+                    value: match &c.value {
+                        ConstantValue::Int(i) => i.to_string(),
+                        ConstantValue::Bool(b) => b.to_string(),
+                        ConstantValue::Str(s) => format!("{:?}", s),
+                        ConstantValue::Pubkey(address) => address.clone(),
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+    })
+}
+
+/// The first 8 bytes of `sha256("namespace:name")`, which Anchor prefixes data with.
+pub(crate) fn discriminator(namespace: &str, name: &str) -> Vec<u8> {
+    hash(format!("{}:{}", namespace, name).as_bytes()).to_bytes()[..8].to_vec()
+}
+
+fn instruction(program: &ProgramModule, ix: &ProgramInstruction) -> Result<IdlInstruction> {
+    let mut accounts: Vec<IdlInstructionAccountItem> = ix
+        .accounts
+        .iter()
+        .map(|a| IdlInstructionAccountItem::Single(account(program, ix, a)))
+        .collect();
+    for p in ix.external_programs.iter() {
+        accounts.push(IdlInstructionAccountItem::Single(IdlInstructionAccount {
+            address: Some(p.address.clone()),
+            ..empty_account(&p.account_name())
+        }));
+    }
+    for p in ix.programs() {
+        accounts.push(IdlInstructionAccountItem::Single(IdlInstructionAccount {
+            address: p.address().map(String::from),
+            ..empty_account(p.account_name())
+        }));
+    }
+    let args = ix
+        .args
+        .iter()
+        .map(|a| {
+            let ty = idl_type(&a.of_type)?;
+            Ok(IdlField {
+                name: a.name.clone(),
+                docs: vec![],
+                ty: match a.optional {
+                    true => IdlType::Option(Box::new(ty)),
+                    false => ty,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(IdlInstruction {
+        name: ix.name.clone(),
+        docs: vec![],
+        discriminator: discriminator("global", &ix.name),
+        accounts,
+        args,
+        returns: None,
+    })
+}
+
+fn empty_account(name: &str) -> IdlInstructionAccount {
+    IdlInstructionAccount {
+        name: name.to_string(),
+        docs: vec![],
+        writable: false,
+        signer: false,
+        optional: false,
+        address: None,
+        pda: None,
+        relations: vec![],
+    }
+}
+
+fn account(
+    program: &ProgramModule,
+    ix: &ProgramInstruction,
+    account: &InstructionAccount,
+) -> IdlInstructionAccount {
+    let constraints = &account.constraints;
+    let pda = match (&constraints.seeds, &constraints.token) {
+        (Some(seeds), _) => pda(program, ix, seeds),
+        (None, Some(token)) if token.is_ata => Some(associated_token_pda(
+            &token.authority,
+            &token.mint,
+            account.of_type.is_interface(),
+        )),
+        _ => None,
+    };
+    // Anchor resolves `address` constraints it can evaluate at build time
+    let address = match &constraints.address {
+        Some(Expression::Const(name)) => match program.constant(name).map(|c| &c.value) {
+            Some(ConstantValue::Pubkey(address)) => Some(address.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    IdlInstructionAccount {
+        writable: account.is_writable(),
+        signer: account.is_signer(),
+        optional: account.optional,
+        address,
+        pda,
+        // `has_one` is described on the account it points to
+        relations: ix
+            .accounts
+            .iter()
+            .filter(|a| a.constraints.has_one.contains(&account.name))
+            .map(|a| a.name.clone())
+            .collect(),
+        ..empty_account(&account.name)
+    }
+}
+
+/// Describes the seeds of a PDA, or nothing if one of them can't be told without running
+/// the program.
+fn pda(program: &ProgramModule, ix: &ProgramInstruction, seeds: &[Seed]) -> Option<IdlPda> {
+    let seeds = seeds
+        .iter()
+        .map(|s| match s {
+            Seed::Str(s) => Some(IdlSeed::Const(IdlSeedConst {
+                value: s.as_bytes().to_vec(),
+            })),
+            Seed::AccountKey(account) => Some(account_seed(account.clone(), None)),
+            Seed::Bytes(Expression::Arg(name)) => {
+                Some(IdlSeed::Arg(IdlSeedArg { path: name.clone() }))
+            }
+            Seed::Bytes(Expression::AccountField { account, field }) => {
+                let of_type = match &ix.account(account)?.of_type {
+                    AccountType::Custom(ty) => program.account(ty).map(|a| a.name.clone()),
+                    _ => None,
+                };
+                Some(account_seed(format!("{}.{}", account, field), of_type))
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(IdlPda {
+        seeds,
+        program: None,
+    })
+}
+
+fn account_seed(path: String, account: Option<String>) -> IdlSeed {
+    IdlSeed::Account(IdlSeedAccount { path, account })
+}
+
+/// Associated token accounts are PDAs of the associated token program, seeded with the
+/// token program they belong to.
+fn associated_token_pda(authority: &str, mint: &str, is_interface: bool) -> IdlPda {
+    let address = |p: BuiltinProgram| {
+        IdlSeed::Const(IdlSeedConst {
+            value: p
+                .address()
+                .and_then(|address| Pubkey::from_str(address).ok())
+                .map(|key| key.to_bytes().to_vec())
+                .unwrap_or_default(),
+        })
+    };
+    let token_program = match is_interface {
+        true => account_seed(
+            BuiltinProgram::TokenInterface.account_name().to_string(),
+            None,
+        ),
+        false => address(BuiltinProgram::Token),
+    };
+    IdlPda {
+        seeds: vec![
+            account_seed(authority.to_string(), None),
+            token_program,
+            account_seed(mint.to_string(), None),
+        ],
+        program: Some(address(BuiltinProgram::AssociatedToken)),
+    }
+}
+
+fn type_def(name: &str, fields: &[ProgramAccountField]) -> Result<IdlTypeDef> {
+    let fields = fields
+        .iter()
+        .map(|f| {
+            Ok(IdlField {
+                name: f.name.clone(),
+                docs: vec![],
+                ty: idl_type(&f.of_type)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(IdlTypeDef {
+        name: name.to_string(),
+        docs: vec![],
+        serialization: Default::default(),
+        repr: None,
+        generics: vec![],
+        ty: IdlTypeDefTy::Struct {
+            fields: Some(IdlDefinedFields::Named(fields)),
+        },
+    })
+}
+
+fn enum_type_def(e: &ProgramEnum) -> IdlTypeDef {
+    IdlTypeDef {
+        name: e.name.clone(),
+        docs: vec![],
+        serialization: Default::default(),
+        repr: None,
+        generics: vec![],
+        ty: IdlTypeDefTy::Enum {
+            variants: e
+                .variants
+                .iter()
+                .map(|v| IdlEnumVariant {
+                    name: v.clone(),
+                    fields: None,
+                })
+                .collect(),
+        },
+    }
+}
+
+/// The IDL type of a Poseidon type.
+fn idl_type(of_type: &str) -> Result<IdlType> {
+    Ok(parsed_idl_type(&PoseidonType::parse(of_type)?))
+}
+
+fn parsed_idl_type(ty: &PoseidonType) -> IdlType {
+    match ty {
+        PoseidonType::Scalar(name) => match *name {
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "u64" | "usize" => IdlType::U64,
+            "i64" | "isize" => IdlType::I64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "Boolean" => IdlType::Bool,
+            _ => IdlType::Pubkey,
+        },
+        PoseidonType::Str => IdlType::String,
+        PoseidonType::Bytes => IdlType::Bytes,
+        PoseidonType::Vec(item) if **item == PoseidonType::Scalar("u8") => IdlType::Bytes,
+        PoseidonType::Vec(item) => IdlType::Vec(Box::new(parsed_idl_type(item))),
+        PoseidonType::Array(item, len) => IdlType::Array(
+            Box::new(parsed_idl_type(item)),
+            IdlArrayLen::Value(*len as usize),
+        ),
+        PoseidonType::Option(item) => IdlType::Option(Box::new(parsed_idl_type(item))),
+        PoseidonType::Defined(name) => IdlType::Defined {
+            name: name.to_string(),
+            generics: vec![],
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang_idl::types::{
+        Idl, IdlInstructionAccount, IdlInstructionAccountItem, IdlPda, IdlSeed, IdlSeedAccount,
+        IdlSeedArg, IdlSeedConst,
+    };
+
+    use super::{discriminator, generate};
+    use crate::transpiler::tests::{lower_source, program};
+
+    const VAULT: &str =
+        "export enum VaultError { Empty = \"nothing to deposit\", Full = \"vault is full\" }
+        export interface Vault extends Account { owner: Pubkey; amount: u64 }";
+
+    fn idl() -> Idl {
+        let lowered = lower_source(&program(
+            "deposit(owner: Signer, vault: Vault, ownerAta: AssociatedTokenAccount, mint: Mint, amount: u64): Result {
+                vault.derive([\"vault\", owner.key, amount.toBytes()]).has([owner]);
+                ownerAta.derive(mint, owner.key);
+                require(amount > 0, VaultError.Empty);
+                vault.amount += amount;
+            }",
+            VAULT,
+        ))
+        .unwrap();
+        generate(&lowered).unwrap()
+    }
+
+    fn account<'a>(idl: &'a Idl, name: &str) -> &'a IdlInstructionAccount {
+        idl.instructions[0]
+            .accounts
+            .iter()
+            .find_map(|a| match a {
+                IdlInstructionAccountItem::Single(a) if a.name == name => Some(a),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn key_bytes(address: &str) -> Vec<u8> {
+        Pubkey::from_str(address).unwrap().to_bytes().to_vec()
+    }
+
+    #[test]
+    fn discriminators_match_anchor() {
+        assert_eq!(
+            discriminator("global", "initialize"),
+            [175, 175, 109, 31, 13, 152, 155, 237]
+        );
+        assert_eq!(
+            discriminator("account", "Vault"),
+            [211, 8, 232, 43, 2, 152, 117, 119]
+        );
+        let idl = idl();
+        assert_eq!(
+            idl.instructions[0].discriminator,
+            [242, 35, 198, 137, 82, 225, 242, 182]
+        );
+        assert_eq!(
+            idl.accounts[0].discriminator,
+            discriminator("account", "Vault")
+        );
+    }
+
+    #[test]
+    fn pda_seeds_are_described() {
+        let idl = idl();
+        assert_eq!(
+            account(&idl, "vault").pda,
+            Some(IdlPda {
+                seeds: vec![
+                    IdlSeed::Const(IdlSeedConst {
+                        value: b"vault".to_vec(),
+                    }),
+                    IdlSeed::Account(IdlSeedAccount {
+                        path: "owner".to_string(),
+                        account: None,
+                    }),
+                    IdlSeed::Arg(IdlSeedArg {
+                        path: "amount".to_string(),
+                    }),
+                ],
+                program: None,
+            })
+        );
+        let ata = |path: &str| {
+            IdlSeed::Account(IdlSeedAccount {
+                path: path.to_string(),
+                account: None,
+            })
+        };
+        assert_eq!(
+            account(&idl, "owner_ata").pda,
+            Some(IdlPda {
+                seeds: vec![
+                    ata("owner"),
+                    IdlSeed::Const(IdlSeedConst {
+                        value: key_bytes("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+                    }),
+                    ata("mint"),
+                ],
+                program: Some(IdlSeed::Const(IdlSeedConst {
+                    value: key_bytes("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+                })),
+            })
+        );
+    }
+
+    #[test]
+    fn errors_are_numbered_from_6000() {
+        let errors: Vec<(u32, String)> =
+            idl().errors.into_iter().map(|e| (e.code, e.name)).collect();
+        assert_eq!(
+            errors,
+            [
+                (6000, "Empty".to_string()),
+                (6001, "Full".to_string()),
+                (6002, "Overflow".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn has_one_is_described_on_the_related_account() {
+        let idl = idl();
+        assert_eq!(account(&idl, "owner").relations, ["vault"]);
+        assert!(account(&idl, "vault").relations.is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+//! Generators turning the `ir` of a program into output artifacts.
+
+pub mod anchor;
+pub mod client;
+pub mod format;
+pub mod idl;
@@ -0,0 +1,222 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use swc_common::{
+    errors::{ColorConfig, Handler},
+    sync::Lrc,
+    SourceMap, Span, DUMMY_SP,
+};
+
+use crate::errors::PoseidonError;
+
+/// A transpiler error pointing at the TypeScript source that caused it.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    pub label: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+            label: None,
+            hint: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Falls back to `span` when the error was raised without a location.
+    pub fn or_span(mut self, span: Span) -> Self {
+        if self.span.is_dummy() {
+            self.span = span;
+        }
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl From<PoseidonError> for Diagnostic {
+    fn from(e: PoseidonError) -> Self {
+        Self {
+            message: e.to_string(),
+            span: DUMMY_SP,
+            label: None,
+            hint: e.hint().map(String::from),
+        }
+    }
+}
+
+impl From<anyhow::Error> for Diagnostic {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<Diagnostic>() {
+            Ok(d) => d,
+            Err(e) => match e.downcast::<PoseidonError>() {
+                Ok(p) => p.into(),
+                Err(e) => Diagnostic::new(DUMMY_SP, e.to_string()),
+            },
+        }
+    }
+}
+
+/// Collects every error of a transpiler run so they can be reported together.
+pub struct Diagnostics {
+    source_map: Lrc<SourceMap>,
+    errors: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new(source_map: Lrc<SourceMap>) -> Self {
+        Self {
+            source_map,
+            errors: vec![],
+        }
+    }
+
+    pub fn source_map(&self) -> &Lrc<SourceMap> {
+        &self.source_map
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.errors.push(diagnostic);
+    }
+
+    /// Records `e`, locating it at `span` if it doesn't carry a location of its own.
+    pub fn report(&mut self, e: anyhow::Error, span: Span) {
+        self.push(Diagnostic::from(e).or_span(span));
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// The collected errors in source order, whatever order they were found in.
+    fn sorted(&self) -> Vec<&Diagnostic> {
+        let mut errors: Vec<&Diagnostic> = self.errors.iter().collect();
+        errors.sort_by_key(|d| d.span.lo);
+        errors
+    }
+
+    /// The messages of the collected errors, in source order.
+    #[cfg(test)]
+    pub fn messages(&self) -> Vec<String> {
+        self.sorted()
+            .into_iter()
+            .map(|d| d.message.clone())
+            .collect()
+    }
+
+    /// Prints every collected error rustc-style and fails if there were any.
+    pub fn emit(&self) -> Result<()> {
+        if !self.has_errors() {
+            return Ok(());
+        }
+        let handler = Handler::with_tty_emitter(
+            ColorConfig::Auto,
+            true,
+            false,
+            Some(self.source_map.clone()),
+        );
+        for d in self.sorted() {
+            let mut builder = if d.span.is_dummy() {
+                handler.struct_err(&d.message)
+            } else {
+                handler.struct_span_err(d.span, &d.message)
+            };
+            if let Some(label) = &d.label {
+                builder.span_label(d.span, label);
+            }
+            if let Some(hint) = &d.hint {
+                builder.help(hint);
+            }
+            builder.emit();
+        }
+        Err(anyhow!(
+            "could not transpile due to {} previous error{}",
+            self.errors.len(),
+            if self.errors.len() == 1 { "" } else { "s" }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_common::SourceMapper;
+
+    use super::Diagnostics;
+    use crate::{ir::ProgramModule, parse_ts::parse_source, transpiler::tests::program};
+
+    /// Lowers `source`, returning the source text each error points at with its message.
+    fn errors(source: &str) -> Vec<(String, String)> {
+        let (module, source_map) = parse_source(source).unwrap();
+        let mut diagnostics = Diagnostics::new(source_map.clone());
+        ProgramModule::from_module(&module, &mut diagnostics);
+        diagnostics
+            .sorted()
+            .into_iter()
+            .map(|d| {
+                assert!(!d.span.is_dummy(), "`{}` has no location", d.message);
+                (
+                    source_map.span_to_snippet(d.span).unwrap(),
+                    d.message.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_expression() {
+        let errors = errors(&program(
+            "set(owner: Signer, state: State): Result {
+                state.derive([\"state\", owner.key]);
+                state.count = 1.5;
+            }",
+            "export interface State extends Account { count: u64 }",
+        ));
+        assert_eq!(
+            errors,
+            [(
+                "1.5".to_string(),
+                "fractional numbers are not supported".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn errors_are_reported_in_source_order() {
+        let errors = errors(&program(
+            "first(owner: Signer, state: State): Result {
+                state.derive([\"state\", owner.key]);
+                state.count = 1.5;
+                state.count = 2.5;
+            }
+            second(owner: Signer, state: Missing): Result {}",
+            "export interface State extends Account { count: u64 }",
+        ));
+        let snippets: Vec<&str> = errors.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(snippets, ["1.5", "2.5", ": Missing"]);
+    }
+}
@@ -1,6 +1,8 @@
-use anchor_lang::error;
+use swc_common::Span;
 use thiserror::*;
 
+use crate::diagnostics::Diagnostic;
+
 #[derive(Debug, Error)]
 pub enum PoseidonError {
     #[error("Invalid type: {0}")]
@@ -15,16 +17,101 @@ pub enum PoseidonError {
     IdentNotFound,
     #[error("expected a Array type")]
     ArrayNotFound,
-    #[error("expected a Call type")]
-    CallNotFound,
     #[error("expected a type reference")]
     TypeReferenceNotFound,
     #[error("expected a TS literal type")]
     TSLiteralTypeNotFound,
     #[error("expected a numeric literal for TS literal type")]
     NumericLiteralNotFound,
-    #[error("expected a Atom type")]
-    AtomNotFound,
-    #[error("expected a authority for the Token account")]
-    AuthorityNotFound,
+    #[error("missing argument {index} for `{callee}`")]
+    MissingArgument { callee: String, index: usize },
+    #[error("missing type annotation")]
+    TypeAnnotationNotFound,
+    #[error("unsupported parameter pattern")]
+    InvalidParameter,
+    #[error("Invalid variable or account type: {0}")]
+    InvalidAccountType(String),
+    #[error("default export must be a class")]
+    DefaultExportNotClass,
+    #[error("invalid export statement")]
+    InvalidExport,
+    #[error("invalid syntax, cannot match this module item")]
+    InvalidModuleItem,
+    #[error("program class undefined")]
+    ProgramClassNotFound,
+    #[error("custom accounts must extend Account type")]
+    AccountNotExtended,
+    #[error("invalid property in custom account")]
+    InvalidProperty,
+    #[error("invalid field in {0}")]
+    InvalidObjectField(String),
+    #[error("invalid program ID")]
+    InvalidProgramId,
+    #[error("invalid class property or member")]
+    InvalidClassMember,
+    #[error("`{0}` is not an account of this instruction")]
+    AccountNotFound(String),
+    #[error("account `{0}` is initialized or closed without deriving its address")]
+    DeriveNotFound(String),
+    #[error("bump not passed in the signer seeds list")]
+    BumpNotFound,
+}
+
+impl PoseidonError {
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            PoseidonError::InvalidType(_) | PoseidonError::KeyWordTypeNotSupported(_) => {
+                Some("use a Poseidon type like `u64`, `Str<N>`, `Vec<T, N>` or `Array<T, N>`, or an exported interface")
+            }
+            PoseidonError::NumericLiteralNotFound => {
+                Some("lengths are given as numeric literals, e.g. `Str<32>`")
+            }
+            PoseidonError::TypeAnnotationNotFound => {
+                Some("annotate the parameter with a Poseidon type, e.g. `amount: u64`")
+            }
+            PoseidonError::InvalidParameter => {
+                Some("destructured and default parameters are not supported")
+            }
+            PoseidonError::InvalidAccountType(_) => Some(
+                "use a standard account type like `Signer` or an interface that extends `Account`",
+            ),
+            PoseidonError::DefaultExportNotClass | PoseidonError::ProgramClassNotFound => {
+                Some("the program is declared with `export default class MyProgram { ... }`")
+            }
+            PoseidonError::InvalidExport => Some(
+                "only `export default class`, `export interface`, `export const` and custom errors are supported",
+            ),
+            PoseidonError::InvalidModuleItem => {
+                Some("top-level code other than imports, exports and constants is not supported")
+            }
+            PoseidonError::AccountNotExtended => {
+                Some("declare custom accounts as `export interface Name extends Account { ... }` and plain structs without `extends`")
+            }
+            PoseidonError::InvalidProperty => Some("fields are declared as `name: type`"),
+            PoseidonError::InvalidObjectField(_) => {
+                Some("fields are given as `name: value`, or just `name` for a value of the same name")
+            }
+            PoseidonError::InvalidProgramId => {
+                Some(r#"expected `static PROGRAM_ID = new Pubkey("11111111111111.....")`"#)
+            }
+            PoseidonError::InvalidClassMember => {
+                Some("the program class may only contain `PROGRAM_ID` and instruction methods")
+            }
+            PoseidonError::AccountNotFound(_) => {
+                Some("pass the account as a parameter of the instruction method")
+            }
+            PoseidonError::DeriveNotFound(_) => Some(
+                r#"use derive or deriveWithBump while using "init" or "initIfNeeded" or "close""#,
+            ),
+            PoseidonError::BumpNotFound => {
+                Some("add the bump as the last element of the signer seeds list")
+            }
+            _ => None,
+        }
+    }
+
+    /// Attaches the location of the offending TypeScript to this error.
+    pub fn at(self, span: Span) -> Diagnostic {
+        Diagnostic::from(self).with_span(span)
+    }
 }
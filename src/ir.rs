@@ -0,0 +1,651 @@
+//! Poseidon's intermediate representation of a program.
+//!
+//! `lower` builds it from the swc AST and the generators in `codegen` consume it, so
+//! nothing in here knows about TypeScript syntax or Rust tokens.
+
+use anchor_lang_idl::types::Idl;
+use swc_common::Span;
+
+#[derive(Debug, Clone)]
+pub struct ProgramModule {
+    pub id: String,
+    pub name: String,
+    pub constants: Vec<ProgramConstant>,
+    pub structs: Vec<ProgramStruct>,
+    pub enums: Vec<ProgramEnum>,
+    pub accounts: Vec<ProgramAccount>,
+    pub instructions: Vec<ProgramInstruction>,
+    pub events: Vec<ProgramEvent>,
+    pub errors: Vec<ProgramError>,
+    pub idls: Vec<IdlImport>,
+}
+
+impl ProgramModule {
+    pub fn new() -> Self {
+        Self {
+            id: "Poseidon11111111111111111111111111111111111".to_string(),
+            name: "AnchorProgram".to_string(),
+            constants: vec![],
+            structs: vec![],
+            enums: vec![],
+            accounts: vec![],
+            instructions: vec![],
+            events: vec![],
+            errors: vec![],
+            idls: vec![],
+        }
+    }
+
+    pub fn constant(&self, name: &str) -> Option<&ProgramConstant> {
+        self.constants.iter().find(|c| c.name == name)
+    }
+
+    pub fn structure(&self, name: &str) -> Option<&ProgramStruct> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    pub fn enumeration(&self, name: &str) -> Option<&ProgramEnum> {
+        self.enums.iter().find(|e| e.name == name)
+    }
+
+    pub fn account(&self, name: &str) -> Option<&ProgramAccount> {
+        self.accounts.iter().find(|a| a.name == name)
+    }
+
+    pub fn event(&self, name: &str) -> Option<&ProgramEvent> {
+        self.events.iter().find(|e| e.name == name)
+    }
+
+    pub fn error(&self, name: &str) -> Option<&ProgramError> {
+        self.errors.iter().find(|e| e.name == name)
+    }
+
+    pub fn idl(&self, name: &str) -> Option<&IdlImport> {
+        self.idls.iter().find(|i| i.name == name)
+    }
+
+    /// The other programs any instruction invokes, each listed once.
+    pub fn external_programs(&self) -> Vec<&ExternalProgram> {
+        let mut programs: Vec<&ExternalProgram> = vec![];
+        for p in self
+            .instructions
+            .iter()
+            .flat_map(|ix| ix.external_programs.iter())
+        {
+            if !programs.contains(&p) {
+                programs.push(p);
+            }
+        }
+        programs
+    }
+}
+
+/// Another Anchor program's IDL, imported with `import Lending from "./lending.json"`.
+#[derive(Debug, Clone)]
+pub struct IdlImport {
+    /// The name it's imported as, which its CPIs are called on.
+    pub name: String,
+    pub idl: Idl,
+}
+
+/// An Anchor program invoked through the CPI module its crate generates with the `cpi`
+/// feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalProgram {
+    /// The snake cased crate name, which is also the name of its `#[program]` module.
+    pub crate_name: String,
+    pub version: String,
+    pub address: String,
+    /// Whether the program was written with Poseidon, which names the accounts struct of an
+    /// instruction `<Instruction>Context` rather than after the instruction alone.
+    pub poseidon: bool,
+}
+
+impl ExternalProgram {
+    /// Name of the account the program is passed in, e.g. `lending_program`.
+    pub fn account_name(&self) -> String {
+        format!("{}_program", self.crate_name)
+    }
+}
+
+/// A custom account declared as `interface X extends Account`.
+#[derive(Debug, Clone)]
+pub struct ProgramAccount {
+    pub name: String,
+    pub fields: Vec<ProgramAccountField>,
+    pub space: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgramAccountField {
+    pub name: String,
+    pub of_type: String,
+    /// The maximum lengths of the `Str`s and `Vec`s in `of_type`, outermost first, as in
+    /// Anchor's `#[max_len]`.
+    pub max_len: Vec<u32>,
+}
+
+/// A plain `interface X` used as the type of fields, serialized inline with Borsh.
+#[derive(Debug, Clone)]
+pub struct ProgramStruct {
+    pub name: String,
+    pub fields: Vec<ProgramAccountField>,
+    pub space: u32,
+}
+
+/// An exported `enum` of plain variants, serialized as the index of the variant.
+#[derive(Debug, Clone)]
+pub struct ProgramEnum {
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+/// An `#[event]` declared as `interface X extends Event`.
+#[derive(Debug, Clone)]
+pub struct ProgramEvent {
+    pub name: String,
+    pub fields: Vec<ProgramAccountField>,
+}
+
+/// A top-level `const`, named in `UPPER_SNAKE_CASE` as in Rust.
+#[derive(Debug, Clone)]
+pub struct ProgramConstant {
+    pub name: String,
+    pub of_type: String,
+    pub value: ConstantValue,
+}
+
+#[derive(Debug, Clone)]
+pub enum ConstantValue {
+    Int(i128),
+    Bool(bool),
+    Str(String),
+    /// The base58 address of a `new Pubkey("...")`.
+    Pubkey(String),
+}
+
+/// A variant of the program's `ErrorCode` enum, declared in TypeScript as an `enum` member
+/// or a class extending `ProgramError`.
+#[derive(Debug, Clone)]
+pub struct ProgramError {
+    pub name: String,
+    pub msg: Option<String>,
+}
+
+impl ProgramError {
+    /// Raised by checked arithmetic that overflows.
+    pub fn overflow() -> Self {
+        Self {
+            name: "Overflow".to_string(),
+            msg: Some("Arithmetic overflow".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgramInstruction {
+    pub name: String,
+    pub accounts: Vec<InstructionAccount>,
+    pub args: Vec<InstructionArgument>,
+    pub body: Vec<Statement>,
+    pub uses_system_program: bool,
+    pub uses_token_program: bool,
+    /// Whether the token program is taken as an `Interface`, for Token-2022 support.
+    pub uses_token_interface: bool,
+    pub uses_associated_token_program: bool,
+    pub uses_checked_arithmetic: bool,
+    /// Programs imported from their IDLs that the instruction invokes, in order of use.
+    pub external_programs: Vec<ExternalProgram>,
+}
+
+impl ProgramInstruction {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            accounts: vec![],
+            args: vec![],
+            body: vec![],
+            uses_system_program: false,
+            uses_token_program: false,
+            uses_token_interface: false,
+            uses_associated_token_program: false,
+            uses_checked_arithmetic: false,
+            external_programs: vec![],
+        }
+    }
+
+    pub fn account(&self, name: &str) -> Option<&InstructionAccount> {
+        self.accounts.iter().find(|a| a.name == name)
+    }
+
+    pub fn account_mut(&mut self, name: &str) -> Option<&mut InstructionAccount> {
+        self.accounts.iter_mut().find(|a| a.name == name)
+    }
+
+    pub fn arg(&self, name: &str) -> Option<&InstructionArgument> {
+        self.args.iter().find(|a| a.name == name)
+    }
+
+    /// The programs that follow the instruction's own accounts, in declaration order.
+    pub fn programs(&self) -> Vec<BuiltinProgram> {
+        let mut programs = vec![];
+        if self.uses_associated_token_program {
+            programs.push(BuiltinProgram::AssociatedToken);
+        }
+        if self.uses_token_program {
+            programs.push(match self.uses_token_interface {
+                true => BuiltinProgram::TokenInterface,
+                false => BuiltinProgram::Token,
+            });
+        }
+        if self.uses_system_program {
+            programs.push(BuiltinProgram::System);
+        }
+        programs
+    }
+}
+
+/// A program passed to an instruction for its CPIs and account constraints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuiltinProgram {
+    AssociatedToken,
+    Token,
+    /// Either the SPL Token or the Token-2022 program, picked by the caller.
+    TokenInterface,
+    System,
+}
+
+impl BuiltinProgram {
+    /// Name of the account the program is passed in.
+    pub fn account_name(&self) -> &'static str {
+        match self {
+            BuiltinProgram::AssociatedToken => "associated_token_program",
+            BuiltinProgram::Token | BuiltinProgram::TokenInterface => "token_program",
+            BuiltinProgram::System => "system_program",
+        }
+    }
+
+    /// The program's address, unless the caller picks it.
+    pub fn address(&self) -> Option<&'static str> {
+        match self {
+            BuiltinProgram::AssociatedToken => Some("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+            BuiltinProgram::Token => Some("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+            BuiltinProgram::TokenInterface => None,
+            BuiltinProgram::System => Some("11111111111111111111111111111111"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionArgument {
+    pub name: String,
+    pub of_type: String,
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountType {
+    Signer,
+    UncheckedAccount,
+    SystemAccount,
+    TokenAccount,
+    AssociatedTokenAccount,
+    Mint,
+    InterfaceTokenAccount,
+    InterfaceAssociatedTokenAccount,
+    InterfaceMint,
+    Custom(String),
+}
+
+impl AccountType {
+    pub fn is_token_account(&self) -> bool {
+        matches!(
+            self,
+            AccountType::TokenAccount | AccountType::InterfaceTokenAccount
+        )
+    }
+
+    pub fn is_mint(&self) -> bool {
+        matches!(self, AccountType::Mint | AccountType::InterfaceMint)
+    }
+
+    pub fn is_associated_token_account(&self) -> bool {
+        matches!(
+            self,
+            AccountType::AssociatedTokenAccount | AccountType::InterfaceAssociatedTokenAccount
+        )
+    }
+
+    /// Token accounts and mints of either token program, through `anchor_spl::token_interface`.
+    pub fn is_interface(&self) -> bool {
+        matches!(
+            self,
+            AccountType::InterfaceTokenAccount
+                | AccountType::InterfaceAssociatedTokenAccount
+                | AccountType::InterfaceMint
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionAccount {
+    pub name: String,
+    pub of_type: AccountType,
+    pub optional: bool,
+    pub constraints: AccountConstraints,
+    pub span: Span,
+}
+
+impl InstructionAccount {
+    pub fn new(name: String, of_type: AccountType, optional: bool, span: Span) -> Self {
+        Self {
+            name,
+            of_type,
+            optional,
+            constraints: AccountConstraints::default(),
+            span,
+        }
+    }
+
+    pub fn is_writable(&self) -> bool {
+        let constraints = &self.constraints;
+        constraints.is_mut
+            || constraints.init.is_some()
+            || constraints.realloc.is_some()
+            || constraints.close.is_some()
+    }
+
+    /// Signers, plus accounts created at a new keypair rather than a PDA.
+    pub fn is_signer(&self) -> bool {
+        let constraints = &self.constraints;
+        let is_ata = constraints.token.as_ref().is_some_and(|t| t.is_ata);
+        self.of_type == AccountType::Signer
+            || (constraints.init.is_some() && constraints.seeds.is_none() && !is_ata)
+    }
+}
+
+/// Everything that ends up in the `#[account(...)]` attribute of an account.
+#[derive(Debug, Clone, Default)]
+pub struct AccountConstraints {
+    pub is_mut: bool,
+    pub init: Option<Init>,
+    pub seeds: Option<Vec<Seed>>,
+    pub bump: Option<Bump>,
+    pub token: Option<TokenConstraint>,
+    pub mint: Option<MintConstraint>,
+    pub has_one: Vec<String>,
+    pub address: Option<Expression>,
+    pub owner: Option<Expression>,
+    pub executable: bool,
+    pub constraint: Vec<Constraint>,
+    pub realloc: Option<Realloc>,
+    pub close: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Init {
+    pub payer: String,
+    pub if_needed: bool,
+    pub space: Option<u32>,
+}
+
+/// `constraint = cond @ ErrorCode::error`, failing with Anchor's `ConstraintRaw` without an error.
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    pub cond: Expression,
+    pub error: Option<String>,
+}
+
+/// Resizes an existing account to `space` bytes, topping up rent from `payer`.
+#[derive(Debug, Clone)]
+pub struct Realloc {
+    pub payer: String,
+    pub space: Expression,
+    pub zero: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Bump {
+    Canonical,
+    Stored(Expression),
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenConstraint {
+    pub mint: String,
+    pub authority: String,
+    pub is_ata: bool,
+}
+
+/// The `mint::*` constraints, set when a mint is initialized or checked.
+#[derive(Debug, Clone, Default)]
+pub struct MintConstraint {
+    pub decimals: Option<Expression>,
+    pub authority: Option<String>,
+    pub freeze_authority: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Seed {
+    Str(String),
+    AccountKey(String),
+    Bytes(Expression),
+    /// The bump closing a list of signer seeds.
+    Bump(Expression),
+}
+
+#[derive(Debug, Clone)]
+pub enum Statement {
+    /// A `let` or `const` local. `of_type` is only set when the source annotates it.
+    Let {
+        name: String,
+        mutable: bool,
+        of_type: Option<String>,
+        value: Expression,
+    },
+    Assign {
+        target: Expression,
+        value: Expression,
+    },
+    Cpi(CpiCall),
+    If {
+        cond: Expression,
+        then: Vec<Statement>,
+        otherwise: Option<Vec<Statement>>,
+    },
+    /// `if let Some(name) = ...`, taken when an optional account or argument is given.
+    IfSome {
+        value: OptionalValue,
+        then: Vec<Statement>,
+        otherwise: Option<Vec<Statement>>,
+    },
+    Block(Vec<Statement>),
+    /// `require!(cond, ErrorCode::error)`
+    Require {
+        cond: Expression,
+        error: String,
+    },
+    /// `return err!(ErrorCode::error)`
+    Throw(String),
+    /// `emit!(Event { field: value, .. })`, with the fields in declaration order.
+    Emit {
+        event: String,
+        fields: Vec<(String, Expression)>,
+    },
+    /// A `switch`, where each arm is taken for any of its values. `otherwise` is the
+    /// `default` case, which is left empty when the values don't cover every case.
+    Match {
+        value: Expression,
+        arms: Vec<(Vec<Expression>, Vec<Statement>)>,
+        otherwise: Option<Vec<Statement>>,
+    },
+    /// An early `return Ok(())`.
+    Return,
+}
+
+/// What an `if let Some(...)` unwraps. The binding shadows the optional value, so inside
+/// it accounts are reached by name like in constraints.
+#[derive(Debug, Clone)]
+pub enum OptionalValue {
+    /// `mutable` when the body assigns fields of the account.
+    Account { name: String, mutable: bool },
+    /// `by_ref` for arguments that aren't `Copy`, like strings.
+    Arg { name: String, by_ref: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CpiProgram {
+    System,
+    Token,
+    /// `TokenProgram.*` in an instruction taking the token program as an `Interface`.
+    TokenInterface,
+    AssociatedToken,
+    External(ExternalProgram),
+}
+
+/// A call into another program, e.g. `TokenProgram.transfer(...)`.
+#[derive(Debug, Clone)]
+pub struct CpiCall {
+    pub program: CpiProgram,
+    /// Snake cased name of the instruction, e.g. `transfer_checked`.
+    pub instruction: String,
+    /// Pairs of (CPI account field, instruction account).
+    pub accounts: Vec<(String, String)>,
+    /// The optional accounts of an external program's instruction, `None` when left out.
+    pub optional_accounts: Vec<(String, Option<String>)>,
+    pub args: Vec<(Expression, ArgPassing)>,
+    pub signer_seeds: Option<Vec<Seed>>,
+}
+
+/// How a CPI helper takes one of its arguments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgPassing {
+    Value,
+    /// `&Pubkey` and `&str` parameters.
+    Ref,
+    /// `String` parameters, which own a copy of the argument.
+    Owned,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Int(i128),
+    Bool(bool),
+    Arg(String),
+    Local(String),
+    /// A program constant, by its Rust name.
+    Const(String),
+    /// A variant of one of the program's enums, like `Status.Open`.
+    Variant {
+        of_type: String,
+        variant: String,
+    },
+    AccountKey(String),
+    AccountField {
+        account: String,
+        field: String,
+    },
+    /// The canonical bump of a PDA found while validating the accounts.
+    Bump(String),
+    Binary {
+        op: BinaryOp,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// `+ - * / %` on integers, which have to say what happens when they overflow.
+    Arithmetic {
+        op: BinaryOp,
+        mode: ArithmeticMode,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    Unary {
+        op: UnaryOp,
+        expr: Box<Expression>,
+    },
+    /// An `as` conversion between the integer types of the two sides of an operation.
+    Cast {
+        expr: Box<Expression>,
+        to: String,
+    },
+    ToBytes(Box<Expression>),
+    /// A copy of a value that isn't `Copy` in Rust, where it would otherwise be moved out.
+    Clone(Box<Expression>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    /// Maps the arithmetic and comparison helpers of Poseidon numeric types.
+    pub fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "add" => Some(BinaryOp::Add),
+            "sub" => Some(BinaryOp::Sub),
+            "mul" => Some(BinaryOp::Mul),
+            "div" => Some(BinaryOp::Div),
+            "eq" => Some(BinaryOp::Eq),
+            "neq" => Some(BinaryOp::Ne),
+            "lt" => Some(BinaryOp::Lt),
+            "lte" => Some(BinaryOp::Le),
+            "gt" => Some(BinaryOp::Gt),
+            "gte" => Some(BinaryOp::Ge),
+            _ => None,
+        }
+    }
+
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArithmeticMode {
+    /// Fails the instruction with `ErrorCode::Overflow`.
+    Checked,
+    Saturating,
+    Wrapping,
+}
+
+impl ArithmeticMode {
+    /// Maps the explicit `saturatingAdd`/`wrappingAdd` style helpers.
+    pub fn from_method(method: &str) -> Option<(BinaryOp, Self)> {
+        match method {
+            "saturatingAdd" => Some((BinaryOp::Add, ArithmeticMode::Saturating)),
+            "saturatingSub" => Some((BinaryOp::Sub, ArithmeticMode::Saturating)),
+            "saturatingMul" => Some((BinaryOp::Mul, ArithmeticMode::Saturating)),
+            "wrappingAdd" => Some((BinaryOp::Add, ArithmeticMode::Wrapping)),
+            "wrappingSub" => Some((BinaryOp::Sub, ArithmeticMode::Wrapping)),
+            "wrappingMul" => Some((BinaryOp::Mul, ArithmeticMode::Wrapping)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
@@ -0,0 +1,3807 @@
+//! Lowers the swc AST of a Poseidon program into the typed model in `ir`.
+
+use std::{fs, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang_idl::types::{Idl, IdlInstructionAccountItem, IdlType};
+use anyhow::{anyhow, Error, Result};
+use convert_case::{Case, Casing};
+use swc_common::{Span, Spanned, DUMMY_SP};
+use swc_ecma_ast::{
+    AssignOp, BindingIdent, CallExpr, Callee, ClassDecl, ClassExpr, ClassMember, ClassMethod,
+    ClassProp, Decl, Expr, ExprOrSpread, IfStmt, ImportDecl, ImportSpecifier, Lit, MemberExpr,
+    Module, ModuleDecl, ModuleItem, Number, Pat, PatOrExpr, Prop, PropName, PropOrSpread, Stmt,
+    SwitchStmt, TsEnumDecl, TsEnumMemberId, TsExprWithTypeArgs, TsInterfaceDecl, TsKeywordTypeKind,
+    TsType, TsTypeAnn, UnaryOp, VarDecl, VarDeclKind, VarDeclarator,
+};
+
+use crate::{
+    codegen::idl,
+    diagnostics::{Diagnostic, Diagnostics},
+    errors::PoseidonError,
+    ir::{
+        self, AccountType, ArgPassing, ArithmeticMode, BinaryOp, BuiltinProgram, Bump,
+        ConstantValue, Constraint, CpiCall, CpiProgram, Expression, ExternalProgram, IdlImport,
+        Init, InstructionAccount, InstructionArgument, MintConstraint, OptionalValue,
+        ProgramAccount, ProgramAccountField, ProgramConstant, ProgramEnum, ProgramError,
+        ProgramEvent, ProgramInstruction, ProgramModule, ProgramStruct, Realloc, Seed, Statement,
+        TokenConstraint,
+    },
+    ts_types::{PoseidonType, STANDARD_TYPES},
+};
+
+/// The shape of a `SystemProgram.*` or `TokenProgram.*` helper.
+struct CpiSignature {
+    name: &'static str,
+    instruction: &'static str,
+    accounts: &'static [&'static str],
+    args: &'static [CpiArg],
+    /// Index of the account that has to sign the CPI.
+    signer: usize,
+}
+
+/// The type a CPI argument must have, which also decides how it's passed.
+#[derive(Clone, Copy)]
+enum CpiArg {
+    /// Any integer, e.g. an amount of lamports or tokens.
+    Int,
+    /// `&Pubkey`
+    Pubkey,
+    /// `&str`
+    Str,
+    /// `String`
+    String,
+}
+
+/// Where a CPI takes more than one signer, `signer` is the one that is usually a PDA: the new
+/// account of `createAccount`, or the `base` that the `*WithSeed` addresses are derived from.
+const SYSTEM_PROGRAM_CPIS: [CpiSignature; 6] = [
+    CpiSignature {
+        name: "transfer",
+        instruction: "transfer",
+        accounts: &["from", "to"],
+        args: &[CpiArg::Int],
+        signer: 0,
+    },
+    CpiSignature {
+        name: "createAccount",
+        instruction: "create_account",
+        accounts: &["from", "to"],
+        args: &[CpiArg::Int, CpiArg::Int, CpiArg::Pubkey],
+        signer: 1,
+    },
+    CpiSignature {
+        name: "allocate",
+        instruction: "allocate",
+        accounts: &["account_to_allocate"],
+        args: &[CpiArg::Int],
+        signer: 0,
+    },
+    CpiSignature {
+        name: "assign",
+        instruction: "assign",
+        accounts: &["account_to_assign"],
+        args: &[CpiArg::Pubkey],
+        signer: 0,
+    },
+    CpiSignature {
+        name: "createAccountWithSeed",
+        instruction: "create_account_with_seed",
+        accounts: &["from", "to", "base"],
+        args: &[CpiArg::Str, CpiArg::Int, CpiArg::Int, CpiArg::Pubkey],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "transferWithSeed",
+        instruction: "transfer_with_seed",
+        accounts: &["from", "base", "to"],
+        args: &[CpiArg::String, CpiArg::Pubkey, CpiArg::Int],
+        signer: 1,
+    },
+];
+
+const TOKEN_PROGRAM_CPIS: [CpiSignature; 12] = [
+    CpiSignature {
+        name: "transfer",
+        instruction: "transfer",
+        accounts: &["from", "to", "authority"],
+        args: &[CpiArg::Int],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "burn",
+        instruction: "burn",
+        accounts: &["mint", "from", "authority"],
+        args: &[CpiArg::Int],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "mintTo",
+        instruction: "mint_to",
+        accounts: &["mint", "to", "authority"],
+        args: &[CpiArg::Int],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "approve",
+        instruction: "approve",
+        accounts: &["to", "delegate", "authority"],
+        args: &[CpiArg::Int],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "approveChecked",
+        instruction: "approve_checked",
+        accounts: &["to", "mint", "delegate", "authority"],
+        args: &[CpiArg::Int, CpiArg::Int],
+        signer: 3,
+    },
+    CpiSignature {
+        name: "closeAccount",
+        instruction: "close_account",
+        accounts: &["account", "destination", "authority"],
+        args: &[],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "freezeAccount",
+        instruction: "freeze_account",
+        accounts: &["account", "mint", "authority"],
+        args: &[],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "initializeAccount",
+        instruction: "initialize_account3",
+        accounts: &["account", "mint", "authority"],
+        args: &[],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "revoke",
+        instruction: "revoke",
+        accounts: &["source", "authority"],
+        args: &[],
+        signer: 1,
+    },
+    CpiSignature {
+        name: "syncNative",
+        instruction: "sync_native",
+        accounts: &["account"],
+        args: &[],
+        signer: 0,
+    },
+    CpiSignature {
+        name: "thawAccount",
+        instruction: "thaw_account",
+        accounts: &["account", "mint", "authority"],
+        args: &[],
+        signer: 2,
+    },
+    CpiSignature {
+        name: "transferChecked",
+        instruction: "transfer_checked",
+        accounts: &["from", "mint", "to", "authority"],
+        args: &[CpiArg::Int, CpiArg::Int],
+        signer: 3,
+    },
+];
+
+/// Both create the ATA of `authority` for `mint`, `createIdempotent` without failing when
+/// it already exists.
+const ASSOCIATED_TOKEN_PROGRAM_CPIS: [CpiSignature; 2] = [
+    CpiSignature {
+        name: "create",
+        instruction: "create",
+        accounts: &["payer", "associated_token", "authority", "mint"],
+        args: &[],
+        signer: 0,
+    },
+    CpiSignature {
+        name: "createIdempotent",
+        instruction: "create_idempotent",
+        accounts: &["payer", "associated_token", "authority", "mint"],
+        args: &[],
+        signer: 0,
+    },
+];
+
+/// The key and value of a property of an object literal, where `{ owner }` is short for
+/// `{ owner: owner }`. `object` names what the literal builds, e.g. "event `Deposited`".
+fn object_prop(prop: &PropOrSpread, object: &str) -> Result<(String, Expr)> {
+    let invalid = |span: Span| PoseidonError::InvalidObjectField(object.to_string()).at(span);
+    match prop.as_prop().map(|p| &**p) {
+        Some(Prop::KeyValue(kv)) => match &kv.key {
+            PropName::Ident(i) => Ok((i.sym.to_string(), *kv.value.clone())),
+            PropName::Str(s) => Ok((s.value.to_string(), *kv.value.clone())),
+            _ => Err(invalid(kv.key.span()))?,
+        },
+        Some(Prop::Shorthand(i)) => Ok((i.sym.to_string(), Expr::Ident(i.clone()))),
+        _ => Err(invalid(prop.span()))?,
+    }
+}
+
+/// Returns the expression passed as the `index`th argument of `c`.
+fn call_arg(c: &CallExpr, index: usize) -> Result<&Expr> {
+    match c.args.get(index) {
+        Some(arg) => Ok(&arg.expr),
+        None => Err(PoseidonError::MissingArgument {
+            callee: callee_name(c),
+            index: index + 1,
+        }
+        .at(c.span))?,
+    }
+}
+
+/// Returns the identifier passed as the `index`th argument of `c`.
+fn ident_arg(c: &CallExpr, index: usize) -> Result<&str> {
+    let arg = call_arg(c, index)?;
+    Ok(arg
+        .as_ident()
+        .ok_or(PoseidonError::IdentNotFound.at(arg.span()))?
+        .sym
+        .as_ref())
+}
+
+/// Returns the elements of the array literal passed as the `index`th argument of `c`.
+fn array_arg(c: &CallExpr, index: usize) -> Result<&Vec<Option<ExprOrSpread>>> {
+    let arg = call_arg(c, index)?;
+    Ok(&arg
+        .as_array()
+        .ok_or(PoseidonError::ArrayNotFound.at(arg.span()))?
+        .elems)
+}
+
+/// Returns the signer seeds passed as the `index`th argument of a CPI call.
+fn seeds_arg(c: &CallExpr, index: usize) -> Result<&Expr> {
+    if c.args.len() <= index {
+        Err(Diagnostic::new(
+            c.span,
+            format!("missing signer seeds for `{}`", callee_name(c)),
+        )
+        .with_label("the authority of this call is a PDA")
+        .with_hint(format!(
+            "pass the seeds array of the PDA as argument {}",
+            index + 1
+        )))?;
+    }
+    call_arg(c, index)
+}
+
+fn callee_name(c: &CallExpr) -> String {
+    match c.callee.as_expr().map(|e| &**e) {
+        Some(Expr::Member(m)) => match (&*m.obj, m.prop.as_ident()) {
+            (Expr::Ident(obj), Some(prop)) => format!("{}.{}", obj.sym, prop.sym),
+            (_, Some(prop)) => prop.sym.to_string(),
+            _ => String::from("call"),
+        },
+        Some(Expr::Ident(i)) => i.sym.to_string(),
+        _ => String::from("call"),
+    }
+}
+
+/// Splits `obj.prop` into the names of the object and the property.
+fn member_names(m: &MemberExpr) -> Result<(&str, &str)> {
+    let obj = m
+        .obj
+        .as_ident()
+        .ok_or(PoseidonError::IdentNotFound.at(m.obj.span()))?
+        .sym
+        .as_ref();
+    let prop = m
+        .prop
+        .as_ident()
+        .ok_or(PoseidonError::IdentNotFound.at(m.prop.span()))?
+        .sym
+        .as_ref();
+    Ok((obj, prop))
+}
+
+/// Splits a chain like `vault.derive([...]).init(owner)` into its root expression and
+/// the chained calls in source order.
+fn call_chain(c: &CallExpr) -> Result<(&Expr, Vec<(&str, &CallExpr)>)> {
+    let mut chain = vec![];
+    let mut call = c;
+    loop {
+        let members = call
+            .callee
+            .as_expr()
+            .ok_or(PoseidonError::ExprNotFound.at(call.span))?
+            .as_member()
+            .ok_or(PoseidonError::MemberNotFound.at(call.callee.span()))?;
+        let prop = members
+            .prop
+            .as_ident()
+            .ok_or(PoseidonError::IdentNotFound.at(members.prop.span()))?
+            .sym
+            .as_ref();
+        chain.push((prop, call));
+        match &*members.obj {
+            Expr::Call(inner) => call = inner,
+            root => {
+                chain.reverse();
+                return Ok((root, chain));
+            }
+        }
+    }
+}
+
+/// The left-hand side of an assignment, as an expression.
+fn assign_target(left: &PatOrExpr) -> Option<Expr> {
+    match left {
+        PatOrExpr::Expr(e) => Some(*e.clone()),
+        PatOrExpr::Pat(p) => match &**p {
+            Pat::Expr(e) => Some(*e.clone()),
+            Pat::Ident(b) => Some(Expr::Ident(b.id.clone())),
+            _ => None,
+        },
+    }
+}
+
+impl ProgramModule {
+    pub fn from_module(module: &Module, diagnostics: &mut Diagnostics) -> Self {
+        let mut program = ProgramModule::new();
+        let mut program_class: Option<&ClassExpr> = None;
+
+        program.add_enums(module, diagnostics);
+        program.add_structs(module, diagnostics);
+        for item in module.body.iter() {
+            match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(i)) if i.src.value.ends_with(".json") => {
+                    program.add_idl(i, diagnostics)
+                }
+                // Other imports only bring the Poseidon types into scope
+                ModuleItem::ModuleDecl(ModuleDecl::Import(_)) => {}
+                // Extract program class
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(default_export_decl)) => {
+                    match default_export_decl.decl.as_class() {
+                        Some(p) => program_class = Some(p),
+                        None => diagnostics.push(
+                            PoseidonError::DefaultExportNotClass.at(default_export_decl.span),
+                        ),
+                    }
+                }
+                // Extract custom accounts
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                    match &export_decl.decl {
+                        // Structs were read up front
+                        Decl::TsInterface(interface) if interface.extends.is_empty() => {}
+                        Decl::TsInterface(interface) if extends(interface, "Event") => {
+                            match ProgramEvent::from_ts_interface(&program, interface) {
+                                Ok(event) => program.events.push(event),
+                                Err(e) => diagnostics.report(e, interface.span),
+                            }
+                        }
+                        Decl::TsInterface(interface) => {
+                            match ProgramAccount::from_ts_expr(&program, interface) {
+                                Ok(custom_account) => program.accounts.push(custom_account),
+                                Err(e) => diagnostics.report(e, interface.span),
+                            }
+                        }
+                        // Extract custom errors
+                        Decl::TsEnum(e) if !is_error_enum(e) => {}
+                        Decl::TsEnum(e) => match ProgramError::from_ts_enum(e) {
+                            Ok(errors) => {
+                                for error in errors {
+                                    program.add_error(error, e.span, diagnostics)
+                                }
+                            }
+                            Err(err) => diagnostics.report(err, e.span),
+                        },
+                        Decl::Class(c) => match ProgramError::from_class_decl(c) {
+                            Ok(error) => program.add_error(error, c.class.span, diagnostics),
+                            Err(e) => diagnostics.report(e, c.class.span),
+                        },
+                        Decl::Var(v) => program.add_constants(v, diagnostics),
+                        _ => diagnostics.push(PoseidonError::InvalidExport.at(export_decl.span)),
+                    }
+                }
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(v))) => program.add_constants(v, diagnostics),
+                _ => diagnostics.push(PoseidonError::InvalidModuleItem.at(item.span())),
+            }
+        }
+
+        match program_class {
+            Some(c) => program.populate_from_class_expr(c, diagnostics),
+            None => diagnostics.push(PoseidonError::ProgramClassNotFound.at(module.span)),
+        }
+        program
+    }
+
+    /// Reads an IDL found by the loader, which CPIs into its program are checked against.
+    fn add_idl(&mut self, import: &ImportDecl, diagnostics: &mut Diagnostics) {
+        let Some(ImportSpecifier::Default(default)) = import.specifiers.first() else {
+            return;
+        };
+        let name = default.local.sym.to_string();
+        let idl = fs::read_to_string(import.src.value.as_ref())
+            .map_err(Error::from)
+            .and_then(|json| Ok(serde_json::from_str::<Idl>(&json)?));
+        match idl {
+            Ok(idl) => match self.idl(&name) {
+                Some(other) if other.idl.address != idl.address => diagnostics.push(
+                    Diagnostic::new(default.span, format!("`{}` is already imported", name))
+                        .with_label("from the IDL of another program"),
+                ),
+                // Imported again by another file
+                Some(_) => {}
+                None => self.idls.push(IdlImport { name, idl }),
+            },
+            Err(e) => diagnostics.push(
+                Diagnostic::new(import.src.span, "expected an Anchor IDL")
+                    .with_label(e.to_string())
+                    .with_hint("Poseidon reads the IDLs of Anchor 0.30 and later, `anchor idl convert` upgrades older ones"),
+            ),
+        }
+    }
+
+    /// Reads the enums that aren't errors up front, as fields may have their type.
+    fn add_enums(&mut self, module: &Module, diagnostics: &mut Diagnostics) {
+        for item in module.body.iter() {
+            let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) = item else {
+                continue;
+            };
+            match &export_decl.decl {
+                Decl::TsEnum(e) if !is_error_enum(e) => match ProgramEnum::from_ts_enum(e) {
+                    Ok(program_enum) => self.enums.push(program_enum),
+                    Err(err) => diagnostics.report(err, e.span),
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the plain interfaces first, so fields can use them whatever order they are
+    /// declared in.
+    fn add_structs(&mut self, module: &Module, diagnostics: &mut Diagnostics) {
+        let interfaces: Vec<&TsInterfaceDecl> = module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) => e.decl.as_ts_interface(),
+                _ => None,
+            })
+            .filter(|i| i.extends.is_empty())
+            .map(|i| &**i)
+            .collect();
+        for interface in interfaces.iter() {
+            self.structs.push(ProgramStruct {
+                name: interface.id.sym.to_string(),
+                fields: vec![],
+                space: 0,
+            });
+        }
+        let mut fields = vec![];
+        for interface in interfaces.iter() {
+            match interface_fields(self, interface) {
+                Ok(f) => fields.push(f),
+                Err(e) => {
+                    diagnostics.report(e, interface.span);
+                    fields.push(vec![]);
+                }
+            }
+        }
+        for (s, f) in self.structs.iter_mut().zip(fields.iter()) {
+            s.fields = f.iter().map(|(field, _)| field.clone()).collect();
+        }
+        for (i, f) in fields.iter().enumerate() {
+            match fields_space(self, f) {
+                Ok(space) => self.structs[i].space = space,
+                Err(e) => diagnostics.report(e, interfaces[i].span),
+            }
+        }
+    }
+
+    /// Fails unless `of_type` is made of Poseidon's types and the program's own ones.
+    fn check_type(&self, of_type: &str) -> Result<(), PoseidonError> {
+        self.check_parsed(&PoseidonType::parse(of_type)?)
+    }
+
+    fn check_parsed(&self, ty: &PoseidonType) -> Result<(), PoseidonError> {
+        match ty {
+            PoseidonType::Vec(item) | PoseidonType::Array(item, _) | PoseidonType::Option(item) => {
+                self.check_parsed(item)
+            }
+            PoseidonType::Defined(name)
+                if self.structure(name).is_none() && self.enumeration(name).is_none() =>
+            {
+                Err(PoseidonError::InvalidType(name.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether values of `of_type` are `Copy` in Rust, which only enums derive of the
+    /// program's own types.
+    fn is_copy(&self, of_type: &str) -> bool {
+        fn is_copy(program: &ProgramModule, ty: &PoseidonType) -> bool {
+            match ty {
+                PoseidonType::Scalar(_) => true,
+                PoseidonType::Array(item, _) | PoseidonType::Option(item) => is_copy(program, item),
+                PoseidonType::Defined(name) => program.enumeration(name).is_some(),
+                PoseidonType::Str | PoseidonType::Bytes | PoseidonType::Vec(_) => false,
+            }
+        }
+        PoseidonType::parse(of_type).is_ok_and(|ty| is_copy(self, &ty))
+    }
+
+    /// The most bytes a value of `of_type` takes once serialized with Borsh, given the
+    /// maximum lengths of its `Str`s and `Vec`s.
+    fn space(&self, of_type: &str, max_len: &[u32]) -> Result<u32, Diagnostic> {
+        self.space_within(&PoseidonType::parse(of_type)?, max_len, &[])
+    }
+
+    /// `space`, for a type found inside the structs in `outer`.
+    fn space_within(
+        &self,
+        ty: &PoseidonType,
+        max_len: &[u32],
+        outer: &[&str],
+    ) -> Result<u32, Diagnostic> {
+        let first_len = |name: &str| {
+            max_len.split_first().ok_or(
+                Diagnostic::new(DUMMY_SP, format!("`{}` needs a maximum length", name))
+                    .with_hint("give it one to size the account, e.g. `Str<32>` or `Vec<u64, 10>`"),
+            )
+        };
+        Ok(match ty {
+            PoseidonType::Scalar(name) => PoseidonType::scalar_size(name),
+            PoseidonType::Str => 4 + first_len("Str")?.0,
+            // TypeScript has no way to bound a `Uint8Array`, which `Vec<u8, N>` does
+            PoseidonType::Bytes => Err(Diagnostic::new(
+                DUMMY_SP,
+                "`Uint8Array` can't be stored without a maximum length",
+            )
+            .with_hint("store bytes as `Vec<u8, N>`, where N is the most it can hold"))?,
+            PoseidonType::Vec(item) => {
+                let (len, rest) = first_len("Vec")?;
+                4 + len * self.space_within(item, rest, outer)?
+            }
+            // Neither the length of an array nor an option's flag takes a maximum length
+            PoseidonType::Array(item, len) => len * self.space_within(item, max_len, outer)?,
+            PoseidonType::Option(item) => 1 + self.space_within(item, max_len, outer)?,
+            // Borsh writes the index of the variant
+            PoseidonType::Defined(name) if self.enumeration(name).is_some() => 1,
+            PoseidonType::Defined(name) if outer.contains(name) => Err(Diagnostic::new(
+                DUMMY_SP,
+                format!("`{}` contains itself", name),
+            )
+            .with_hint("a struct can't be a field of itself, even through other structs"))?,
+            PoseidonType::Defined(name) => {
+                let s = self
+                    .structure(name)
+                    .ok_or(PoseidonError::InvalidType(name.to_string()))?;
+                let outer = [outer, &[name]].concat();
+                let mut space = 0;
+                for field in s.fields.iter() {
+                    let field_type = PoseidonType::parse(&field.of_type)?;
+                    space += self.space_within(&field_type, &field.max_len, &outer)?;
+                }
+                space
+            }
+        })
+    }
+
+    fn add_constants(&mut self, v: &VarDecl, diagnostics: &mut Diagnostics) {
+        if v.kind != VarDeclKind::Const {
+            diagnostics.push(
+                Diagnostic::new(v.span, "only `const` can be declared outside the program")
+                    .with_hint("program state lives in accounts, not in variables"),
+            );
+            return;
+        }
+        for d in v.decls.iter() {
+            match ProgramConstant::from_var_declarator(d) {
+                Ok(constant) if self.constant(&constant.name).is_some() => {
+                    diagnostics.push(Diagnostic::new(
+                        d.span,
+                        format!("constant `{}` is declared more than once", constant.name),
+                    ))
+                }
+                Ok(constant) => self.constants.push(constant),
+                Err(e) => diagnostics.report(e, d.span),
+            }
+        }
+    }
+
+    fn add_error(&mut self, error: ProgramError, span: Span, diagnostics: &mut Diagnostics) {
+        if self.error(&error.name).is_some() {
+            diagnostics.push(Diagnostic::new(
+                span,
+                format!("error `{}` is declared more than once", error.name),
+            ));
+        } else {
+            self.errors.push(error);
+        }
+    }
+
+    pub fn populate_from_class_expr(&mut self, class: &ClassExpr, diagnostics: &mut Diagnostics) {
+        match &class.ident {
+            Some(ident) => {
+                self.name = ident
+                    .as_ref()
+                    .split('#')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            }
+            None => diagnostics.push(
+                Diagnostic::new(class.class.span, "expected program to have a valid name")
+                    .with_hint("name the program class, e.g. `export default class MyProgram`"),
+            ),
+        }
+        for member in class.class.body.iter() {
+            let lowered = match member {
+                // Handle as a class prop
+                ClassMember::ClassProp(c) => self.set_program_id(c),
+                ClassMember::Method(c) => {
+                    ProgramInstruction::from_class_method(self, c, diagnostics)
+                        .map(|ix| self.instructions.push(ix))
+                }
+                _ => Err(PoseidonError::InvalidClassMember.at(member.span()).into()),
+            };
+            if let Err(e) = lowered {
+                diagnostics.report(e, member.span());
+            }
+        }
+        if self
+            .instructions
+            .iter()
+            .any(|ix| ix.uses_checked_arithmetic)
+            && self.error("Overflow").is_none()
+        {
+            self.errors.push(ProgramError::overflow());
+        }
+        self.check_token_programs(diagnostics);
+    }
+
+    /// `anchor_spl::token` and `anchor_spl::token_interface` export items of the same
+    /// names, so a program sticks to one of them.
+    fn check_token_programs(&self, diagnostics: &mut Diagnostics) {
+        let accounts = || self.instructions.iter().flat_map(|ix| ix.accounts.iter());
+        let Some(interface) = accounts().find(|a| a.of_type.is_interface()) else {
+            return;
+        };
+        let classic = accounts().find(|a| {
+            matches!(
+                a.of_type,
+                AccountType::TokenAccount | AccountType::AssociatedTokenAccount | AccountType::Mint
+            )
+        });
+        let classic_cpi = self
+            .instructions
+            .iter()
+            .any(|ix| ix.uses_token_program && !ix.uses_token_interface);
+        let span = match classic {
+            Some(account) => account.span,
+            None if classic_cpi => interface.span,
+            None => return,
+        };
+        diagnostics.push(
+            Diagnostic::new(span, "SPL Token and Token-2022 accounts can't be mixed in a program")
+                .with_hint("use `InterfaceTokenAccount`, `InterfaceAssociatedTokenAccount` and `InterfaceMint` throughout, which work with both token programs"),
+        );
+    }
+
+    fn set_program_id(&mut self, c: &ClassProp) -> Result<()> {
+        if c.key.as_ident().map(|i| i.sym.as_ref()) != Some("PROGRAM_ID") {
+            Err(PoseidonError::InvalidClassMember.at(c.key.span()))?
+        }
+        let val = c
+            .value
+            .as_ref()
+            .and_then(|v| v.as_new())
+            .filter(|v| v.callee.as_ident().is_some_and(|i| i.sym == "Pubkey"))
+            .ok_or(PoseidonError::InvalidProgramId.at(c.span))?;
+        self.id = match val
+            .args
+            .as_ref()
+            .and_then(|args| args.first())
+            .map(|arg| &*arg.expr)
+        {
+            Some(Expr::Lit(Lit::Str(s))) => s.value.to_string(),
+            _ => Err(PoseidonError::InvalidProgramId.at(val.span))?,
+        };
+        Ok(())
+    }
+}
+
+impl ProgramInstruction {
+    pub fn from_class_method(
+        program: &ProgramModule,
+        c: &ClassMethod,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Self> {
+        // Get name
+        let name = c
+            .key
+            .as_ident()
+            .ok_or(PoseidonError::IdentNotFound.at(c.key.span()))?
+            .sym
+            .to_string();
+        let mut lowering = MethodLowering {
+            program,
+            ix: ProgramInstruction::new(name.to_case(Case::Snake)),
+            depth: 0,
+            scopes: vec![],
+            unwrapped: vec![],
+        };
+        // Get accounts and args
+        for p in c.function.params.iter() {
+            lowering.add_param(&p.pat, p.span)?;
+        }
+
+        let stmts = &c
+            .function
+            .body
+            .as_ref()
+            .ok_or(anyhow!("block statement none"))?
+            .stmts;
+        let body = lowering.lower_block(stmts, diagnostics);
+        let mut ix = lowering.ix;
+        ix.body = body;
+        // this is evaluated this way coz, ta might not have seeds
+        for acc in ix.accounts.iter() {
+            let constraints = &acc.constraints;
+            // Mints may be created at a new keypair instead
+            if constraints.seeds.is_none()
+                && constraints.token.is_none()
+                && constraints.mint.is_none()
+                && (constraints.close.is_some() || constraints.init.is_some())
+            {
+                diagnostics.push(PoseidonError::DeriveNotFound(acc.name.clone()).at(acc.span));
+            }
+        }
+
+        Ok(ix)
+    }
+}
+
+/// State threaded through the lowering of a single instruction method.
+struct MethodLowering<'a> {
+    program: &'a ProgramModule,
+    ix: ProgramInstruction,
+    /// How many blocks deep the statement being lowered is.
+    depth: usize,
+    /// The locals declared in each enclosing block, innermost last.
+    scopes: Vec<Vec<Local>>,
+    /// Optional accounts and arguments known to be present here.
+    unwrapped: Vec<String>,
+}
+
+/// A `let` or `const` binding of an instruction body.
+struct Local {
+    name: String,
+    of_type: String,
+    constant: bool,
+    reassigned: bool,
+    /// The elements of a `Seeds` array, which are spliced in wherever the local is used.
+    seeds: Option<Vec<Option<ExprOrSpread>>>,
+}
+
+impl MethodLowering<'_> {
+    fn add_param(&mut self, pat: &Pat, span: Span) -> Result<()> {
+        let BindingIdent { id, type_ann } = pat
+            .as_ident()
+            .ok_or(PoseidonError::InvalidParameter.at(span))?;
+        let name = id.sym.to_string().to_case(Case::Snake);
+        let binding = type_ann
+            .as_ref()
+            .ok_or(PoseidonError::TypeAnnotationNotFound.at(span))?;
+        let (of_type, _max_len) = extract_type(binding)?;
+        // `name?: Type`
+        let optional = id.optional;
+
+        if self.program.check_type(&of_type).is_ok() {
+            self.ix.args.push(InstructionArgument {
+                name,
+                of_type,
+                optional,
+            });
+            return Ok(());
+        }
+
+        let account_type = match of_type.as_str() {
+            "Signer" => AccountType::Signer,
+            "UncheckedAccount" => AccountType::UncheckedAccount,
+            "SystemAccount" => AccountType::SystemAccount,
+            "TokenAccount" => AccountType::TokenAccount,
+            "AssociatedTokenAccount" => AccountType::AssociatedTokenAccount,
+            "Mint" => AccountType::Mint,
+            "InterfaceTokenAccount" => AccountType::InterfaceTokenAccount,
+            "InterfaceAssociatedTokenAccount" => AccountType::InterfaceAssociatedTokenAccount,
+            "InterfaceMint" => AccountType::InterfaceMint,
+            _ if self.program.account(&of_type).is_some() => AccountType::Custom(of_type.clone()),
+            _ => Err(PoseidonError::InvalidAccountType(of_type).at(binding.span))?,
+        };
+        let mut account = InstructionAccount::new(name, account_type, optional, span);
+        if account.of_type.is_interface() {
+            self.ix.uses_token_interface = true;
+        }
+        match &account.of_type {
+            AccountType::Signer | AccountType::Mint | AccountType::InterfaceMint => {
+                account.constraints.is_mut = true
+            }
+            AccountType::SystemAccount => {
+                self.ix.uses_system_program = true;
+                account.constraints.is_mut = true;
+            }
+            AccountType::AssociatedTokenAccount | AccountType::InterfaceAssociatedTokenAccount => {
+                self.ix.uses_associated_token_program = true;
+                self.ix.uses_token_program = true;
+            }
+            AccountType::TokenAccount | AccountType::InterfaceTokenAccount => {
+                self.ix.uses_token_program = true
+            }
+            AccountType::Custom(_) => self.ix.uses_system_program = true,
+            AccountType::UncheckedAccount => {}
+        }
+        self.ix.accounts.push(account);
+        Ok(())
+    }
+
+    /// Lowers the statements of a block, reporting every statement that fails on its own.
+    fn lower_block(&mut self, stmts: &[Stmt], diagnostics: &mut Diagnostics) -> Vec<Statement> {
+        let mut lowered: Vec<Statement> = vec![];
+        self.scopes.push(vec![]);
+        for s in stmts.iter() {
+            match self.lower_stmt(s, diagnostics) {
+                Ok(stmts) => lowered.extend(stmts),
+                Err(e) => diagnostics.report(e, s.span()),
+            }
+        }
+        // Only locals that are assigned again need `let mut`
+        for local in self.scopes.pop().unwrap_or_default() {
+            if !local.reassigned {
+                continue;
+            }
+            let decl = lowered
+                .iter_mut()
+                .rev()
+                .find(|s| matches!(s, Statement::Let { name, .. } if *name == local.name));
+            if let Some(Statement::Let { mutable, .. }) = decl {
+                *mutable = true;
+            }
+        }
+        lowered
+    }
+
+    /// Lowers the body of an `if`/`else` or a nested block.
+    fn lower_nested(&mut self, s: &Stmt, diagnostics: &mut Diagnostics) -> Vec<Statement> {
+        self.depth += 1;
+        let lowered = match s {
+            Stmt::Block(b) => self.lower_block(&b.stmts, diagnostics),
+            _ => self.lower_block(std::slice::from_ref(s), diagnostics),
+        };
+        self.depth -= 1;
+        lowered
+    }
+
+    fn lower_stmt(&mut self, s: &Stmt, diagnostics: &mut Diagnostics) -> Result<Vec<Statement>> {
+        let stmt = match s {
+            Stmt::Expr(e) => self.lower_expr_stmt(&e.expr)?,
+            Stmt::If(i) => Some(self.lower_if(i, diagnostics)?),
+            Stmt::Switch(sw) => Some(self.lower_switch(sw, diagnostics)?),
+            Stmt::Block(_) => Some(Statement::Block(self.lower_nested(s, diagnostics))),
+            Stmt::Return(r) => match &r.arg {
+                Some(arg) => Err(Diagnostic::new(
+                    arg.span(),
+                    "instructions cannot return a value",
+                )
+                .with_hint("use `return;` to end the instruction early"))?,
+                None => Some(Statement::Return),
+            },
+            Stmt::Throw(t) => Some(Statement::Throw(self.raised_error(&t.arg)?)),
+            Stmt::Empty(_) => None,
+            Stmt::Decl(Decl::Var(v)) => return self.lower_var_decl(v),
+            _ => Err(unsupported_stmt(s.span()))?,
+        };
+        Ok(stmt.into_iter().collect())
+    }
+
+    /// Lowers an `if`, or an `if let Some(...)` when it checks for an optional account or
+    /// argument.
+    fn lower_if(&mut self, i: &IfStmt, diagnostics: &mut Diagnostics) -> Result<Statement> {
+        let Some(name) = self.optional_name(&i.test) else {
+            let cond = self.lower_expr(&i.test)?;
+            let then = self.lower_nested(&i.cons, diagnostics);
+            let otherwise = i
+                .alt
+                .as_ref()
+                .map(|alt| self.lower_nested(alt, diagnostics));
+            return Ok(Statement::If {
+                cond,
+                then,
+                otherwise,
+            });
+        };
+        self.unwrapped.push(name.clone());
+        let then = self.lower_nested(&i.cons, diagnostics);
+        self.unwrapped.pop();
+        let otherwise = i
+            .alt
+            .as_ref()
+            .map(|alt| self.lower_nested(alt, diagnostics));
+        let value = match self.ix.arg(&name) {
+            Some(arg) => OptionalValue::Arg {
+                by_ref: !self.program.is_copy(&arg.of_type),
+                name,
+            },
+            None => OptionalValue::Account {
+                mutable: assigns_to(&then, &name),
+                name,
+            },
+        };
+        Ok(Statement::IfSome {
+            value,
+            then,
+            otherwise,
+        })
+    }
+
+    /// Lowers a `switch` into a `match`. Cases end with `break` unless they leave the
+    /// instruction, and empty cases share the body of the case after them.
+    fn lower_switch(
+        &mut self,
+        sw: &SwitchStmt,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Statement> {
+        let value = self.lower_expr(&sw.discriminant)?;
+        let of_type = self.type_of(&value);
+        let mut arms: Vec<(Vec<Expression>, Vec<Statement>)> = vec![];
+        let mut otherwise: Option<Vec<Statement>> = None;
+        let mut values: Vec<Expression> = vec![];
+        let mut is_default = false;
+        for (i, case) in sw.cases.iter().enumerate() {
+            match &case.test {
+                Some(test) => values.push(self.case_value(test, of_type.as_deref())?),
+                None => is_default = true,
+            }
+            if case.cons.is_empty() && i + 1 < sw.cases.len() {
+                continue;
+            }
+            // `case X: { ... }`
+            let cons = match case.cons.as_slice() {
+                [Stmt::Block(b)] => &b.stmts[..],
+                cons => cons,
+            };
+            let stmts = match cons.split_last() {
+                Some((Stmt::Break(b), rest)) if b.label.is_none() => rest,
+                Some((Stmt::Return(_) | Stmt::Throw(_), _)) | None => cons,
+                Some((last, _)) => Err(Diagnostic::new(last.span(), "cases can't fall through")
+                    .with_hint("end the case with `break`"))?,
+            };
+            self.depth += 1;
+            let body = self.lower_block(stmts, diagnostics);
+            self.depth -= 1;
+            // Other values sharing the body of `default` are covered by it anyway
+            match is_default {
+                true => otherwise = Some(body),
+                false => arms.push((values, body)),
+            }
+            values = vec![];
+            is_default = false;
+        }
+        let covered = of_type
+            .as_deref()
+            .and_then(|ty| self.program.enumeration(ty))
+            .is_some_and(|e| {
+                e.variants.iter().all(|v| {
+                    arms.iter().flat_map(|(values, _)| values.iter()).any(
+                        |value| matches!(value, Expression::Variant { variant, .. } if variant == v),
+                    )
+                })
+            });
+        if otherwise.is_none() && !covered {
+            otherwise = Some(vec![]);
+        }
+        Ok(Statement::Match {
+            value,
+            arms,
+            otherwise,
+        })
+    }
+
+    /// Lowers the value of a `case`, which has to be an enum variant or a literal.
+    fn case_value(&mut self, test: &Expr, of_type: Option<&str>) -> Result<Expression> {
+        let value = self.lower_expr(test)?;
+        match &value {
+            Expression::Variant { of_type: ty, .. } if of_type.is_some_and(|t| t != ty) => {
+                Err(Diagnostic::new(
+                    test.span(),
+                    format!("expected a `{}`", of_type.unwrap_or_default()),
+                )
+                .with_label(format!("this is a `{}`", ty)))?
+            }
+            Expression::Variant { .. } | Expression::Int(_) | Expression::Bool(_) => Ok(value),
+            _ => Err(Diagnostic::new(test.span(), "unsupported case value")
+                .with_hint("cases are enum variants like `Status.Open` or literals"))?,
+        }
+    }
+
+    /// Lowers `let`/`const` declarations, one `Statement::Let` per declarator.
+    fn lower_var_decl(&mut self, v: &VarDecl) -> Result<Vec<Statement>> {
+        let mut lowered: Vec<Statement> = vec![];
+        for d in v.decls.iter() {
+            let BindingIdent { id, type_ann } = d.name.as_ident().ok_or(
+                Diagnostic::new(d.name.span(), "destructuring is not supported")
+                    .with_hint("declare one local at a time"),
+            )?;
+            let init = d.init.as_ref().ok_or(
+                Diagnostic::new(d.span, format!("`{}` needs an initial value", id.sym))
+                    .with_hint("locals have to be initialized where they are declared"),
+            )?;
+            let annotated = match type_ann {
+                Some(ann) => Some(extract_type(ann)?.0),
+                None => None,
+            };
+            let mut local = Local {
+                name: id.sym.as_ref().to_case(Case::Snake),
+                of_type: String::from("Seeds"),
+                constant: v.kind == VarDeclKind::Const,
+                reassigned: false,
+                seeds: None,
+            };
+            // Seeds only exist at compile time, the array is used as is wherever the local is
+            if annotated.as_deref() == Some("Seeds") || (annotated.is_none() && init.is_array()) {
+                let elems = &init
+                    .as_array()
+                    .ok_or(PoseidonError::ArrayNotFound.at(init.span()))?
+                    .elems;
+                local.seeds = Some(elems.clone());
+                self.declare(local);
+                // Declared all the same, so that its uses don't fail too
+                if annotated.is_none() {
+                    Err(
+                        Diagnostic::new(init.span(), "arrays can only be declared as `Seeds`")
+                            .with_hint(format!(
+                                "annotate it if these are seeds, e.g. `const {}: Seeds = [...]`",
+                                id.sym
+                            )),
+                    )?
+                }
+                continue;
+            }
+            if let (Some(ty), Some(ann)) = (&annotated, type_ann) {
+                self.program
+                    .check_type(ty)
+                    .map_err(|e| Diagnostic::from(e).with_span(ann.span))?;
+            }
+            let value = self.lower_expr(init)?;
+            local.of_type = annotated.clone().or_else(|| self.type_of(&value)).ok_or(
+                Diagnostic::new(id.span, format!("cannot infer the type of `{}`", id.sym))
+                    .with_hint(format!("annotate it, e.g. `let {}: u64 = ...`", id.sym)),
+            )?;
+            let value = self.owned(self.coerce(value, annotated.clone()));
+            lowered.push(Statement::Let {
+                name: local.name.clone(),
+                mutable: false,
+                of_type: annotated,
+                value,
+            });
+            self.declare(local);
+        }
+        Ok(lowered)
+    }
+
+    fn declare(&mut self, local: Local) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.retain(|l| l.name != local.name);
+            scope.push(local);
+        }
+    }
+
+    /// The name of the optional account or argument that `test` checks for, as in
+    /// `if (vault) { ... }`.
+    fn optional_name(&self, test: &Expr) -> Option<String> {
+        match test {
+            Expr::Ident(i) => {
+                let name = i.sym.as_ref().to_case(Case::Snake);
+                let optional = self.local(&name).is_none()
+                    && (self.ix.arg(&name).is_some_and(|a| a.optional)
+                        || self.ix.account(&name).is_some_and(|a| a.optional));
+                optional.then_some(name)
+            }
+            Expr::Paren(p) => self.optional_name(&p.expr),
+            _ => None,
+        }
+    }
+
+    /// Fails if `name` is an optional account or argument used outside of an `if (name)`.
+    fn check_present(&self, name: &str, span: Span) -> Result<()> {
+        let optional = self.ix.arg(name).is_some_and(|a| a.optional)
+            || self.ix.account(name).is_some_and(|a| a.optional);
+        if optional && !self.unwrapped.iter().any(|n| n == name) {
+            Err(Diagnostic::new(span, format!("`{}` may be missing", name))
+                .with_label("this is optional")
+                .with_hint(format!("check for it with `if ({}) {{ ... }}`", name)))?
+        }
+        Ok(())
+    }
+
+    /// Finds the innermost local called `name`.
+    fn local(&self, name: &str) -> Option<&Local> {
+        self.scopes.iter().rev().flatten().find(|l| l.name == name)
+    }
+
+    fn local_mut(&mut self, name: &str) -> Option<&mut Local> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flatten()
+            .find(|l| l.name == name)
+    }
+
+    /// Returns the elements of the seeds array `e`, looking through `Seeds` locals.
+    fn seeds_of(&self, e: &Expr) -> Result<Vec<Option<ExprOrSpread>>> {
+        if let Expr::Ident(i) = e {
+            let seeds = self
+                .local(&i.sym.as_ref().to_case(Case::Snake))
+                .and_then(|l| l.seeds.clone());
+            if let Some(seeds) = seeds {
+                return Ok(seeds);
+            }
+        }
+        Ok(e.as_array()
+            .ok_or(PoseidonError::ArrayNotFound.at(e.span()))?
+            .elems
+            .clone())
+    }
+
+    fn lower_expr_stmt(&mut self, expr: &Expr) -> Result<Option<Statement>> {
+        match expr {
+            Expr::Call(c) if callee_name(c) == "require" => {
+                let cond = self.lower_expr(call_arg(c, 0)?)?;
+                let error = self.raised_error(call_arg(c, 1)?)?;
+                Ok(Some(Statement::Require { cond, error }))
+            }
+            Expr::Call(c) if callee_name(c) == "emit" => Ok(Some(self.lower_emit(c)?)),
+            Expr::Call(c) => {
+                let (root, chain) = call_chain(c)?;
+                let root_name = root
+                    .as_ident()
+                    .ok_or(PoseidonError::IdentNotFound.at(root.span()))?
+                    .sym
+                    .as_ref();
+                match root_name {
+                    "SystemProgram" | "TokenProgram" | "AssociatedTokenProgram" => {
+                        let cpi_program = match root_name {
+                            "SystemProgram" => CpiProgram::System,
+                            "AssociatedTokenProgram" => CpiProgram::AssociatedToken,
+                            _ if self.ix.uses_token_interface => CpiProgram::TokenInterface,
+                            _ => CpiProgram::Token,
+                        };
+                        let (method, call) = chain[0];
+                        let cpi = self.lower_cpi(cpi_program, method, call)?;
+                        Ok(Some(Statement::Cpi(cpi)))
+                    }
+                    _ => {
+                        // Instructions of programs imported from their IDLs
+                        if let Some(import) = self.program.idl(root_name) {
+                            let (method, call) = chain[0];
+                            let cpi = self.lower_external_cpi(import, method, call)?;
+                            return Ok(Some(Statement::Cpi(cpi)));
+                        }
+                        let name = root_name.to_case(Case::Snake);
+                        if self.ix.account(&name).is_none() {
+                            Err(PoseidonError::AccountNotFound(root_name.to_string())
+                                .at(root.span()))?
+                        }
+                        if self.depth > 0 {
+                            Err(Diagnostic::new(
+                                c.span,
+                                "account constraints cannot be set conditionally",
+                            )
+                            .with_hint("move this call to the top level of the instruction"))?
+                        }
+                        for (method, call) in chain {
+                            self.lower_account_method(&name, method, call)?;
+                        }
+                        Ok(None)
+                    }
+                }
+            }
+            Expr::Assign(a) => {
+                let target_expr = assign_target(&a.left)
+                    .filter(|t| t.is_member() || t.is_ident())
+                    .ok_or(PoseidonError::MemberNotFound.at(a.left.span()))?;
+                let target = self.lower_expr(&target_expr)?;
+                match &target {
+                    Expression::AccountField { account, .. } => {
+                        self.ix
+                            .account_mut(account)
+                            .ok_or(anyhow!("account not found"))?
+                            .constraints
+                            .is_mut = true;
+                    }
+                    Expression::Local(name) => {
+                        let local = self.local_mut(name).ok_or(anyhow!("local not found"))?;
+                        if local.constant {
+                            Err(Diagnostic::new(
+                                target_expr.span(),
+                                format!("cannot assign twice to constant `{}`", name),
+                            )
+                            .with_hint("declare it with `let` to make it mutable"))?
+                        }
+                        local.reassigned = true;
+                    }
+                    _ => Err(Diagnostic::new(
+                        target_expr.span(),
+                        "only locals and fields of accounts can be assigned",
+                    ))?,
+                }
+                let mut value = self.lower_expr(&a.right)?;
+                if a.op != AssignOp::Assign {
+                    // `x += y` is lowered as `x = x + y`
+                    let op = assign_op(a.op).ok_or(unsupported_expr(a.span))?;
+                    value = self.binary(op, ArithmeticMode::Checked, target.clone(), value);
+                }
+                let value = self.owned(self.coerce(value, self.type_of(&target)));
+                Ok(Some(Statement::Assign { target, value }))
+            }
+            Expr::Paren(p) => self.lower_expr_stmt(&p.expr),
+            _ => Err(unsupported_stmt(expr.span()).into()),
+        }
+    }
+
+    /// Lowers `emit(new MyEvent({ ... }))` and `emit<MyEvent>({ ... })`.
+    fn lower_emit(&mut self, c: &CallExpr) -> Result<Statement> {
+        let arg = call_arg(c, 0)?;
+        let (name, object) = match (arg, c.type_args.as_deref()) {
+            (Expr::New(n), _) => (
+                n.callee
+                    .as_ident()
+                    .ok_or(PoseidonError::IdentNotFound.at(n.callee.span()))?,
+                n.args
+                    .as_ref()
+                    .and_then(|args| args.first())
+                    .map(|a| &*a.expr),
+            ),
+            (object, Some(type_args)) => (
+                type_args
+                    .params
+                    .first()
+                    .and_then(|t| t.as_ts_type_ref())
+                    .and_then(|r| r.type_name.as_ident())
+                    .ok_or(PoseidonError::TypeReferenceNotFound.at(type_args.span))?,
+                Some(object),
+            ),
+            _ => Err(
+                Diagnostic::new(arg.span(), "cannot tell which event is emitted")
+                    .with_hint("write `emit(new MyEvent({ ... }))` or `emit<MyEvent>({ ... })`"),
+            )?,
+        };
+        let program = self.program;
+        let event = program.event(name.sym.as_ref()).ok_or(
+            Diagnostic::new(name.span, format!("cannot find event `{}`", name.sym))
+                .with_hint("declare it as `export interface Name extends Event { ... }`"),
+        )?;
+        let object = object.and_then(|o| o.as_object()).ok_or(
+            Diagnostic::new(c.span, "events are built from an object literal")
+                .with_hint("pass every field of the event, e.g. `{ amount, user: user.key }`"),
+        )?;
+
+        let mut values: Vec<(String, Expr)> = vec![];
+        for prop in object.props.iter() {
+            let (key, value) = object_prop(prop, &format!("event `{}`", event.name))?;
+            let field = key.to_case(Case::Snake);
+            if !event.fields.iter().any(|f| f.name == field) {
+                Err(Diagnostic::new(
+                    prop.span(),
+                    format!("event `{}` has no field `{}`", event.name, key),
+                ))?
+            }
+            values.push((field, value));
+        }
+        let mut fields: Vec<(String, Expression)> = vec![];
+        for field in event.fields.iter() {
+            let (_, value) =
+                values
+                    .iter()
+                    .find(|(name, ..)| *name == field.name)
+                    .ok_or(Diagnostic::new(
+                        object.span,
+                        format!("missing field `{}` of event `{}`", field.name, event.name),
+                    ))?;
+            let value = self.lower_expr(value)?;
+            fields.push((
+                field.name.clone(),
+                self.owned(self.coerce(value, Some(field.of_type.clone()))),
+            ));
+        }
+        Ok(Statement::Emit {
+            event: event.name.clone(),
+            fields,
+        })
+    }
+
+    fn lower_account_method(&mut self, name: &str, method: &str, c: &CallExpr) -> Result<()> {
+        let account = self.ix.account(name).ok_or(anyhow!("account not found"))?;
+        let of_type = account.of_type.clone();
+        match method {
+            "derive" | "deriveWithBump" => {
+                let mut constraints = account.constraints.clone();
+                if of_type.is_associated_token_account() {
+                    constraints.token = Some(TokenConstraint {
+                        mint: ident_arg(c, 0)?.to_case(Case::Snake),
+                        authority: token_authority(c, 1)?,
+                        is_ata: true,
+                    });
+                    constraints.is_mut = true;
+                } else if of_type.is_token_account() {
+                    constraints.token = Some(TokenConstraint {
+                        mint: ident_arg(c, 1)?.to_case(Case::Snake),
+                        authority: token_authority(c, 2)?,
+                        is_ata: false,
+                    });
+                    constraints.is_mut = true;
+                }
+                if !of_type.is_associated_token_account() {
+                    let seeds = self.seeds_of(call_arg(c, 0)?)?;
+                    let seeds = self.lower_seeds(&seeds, false)?;
+                    if seeds
+                        .iter()
+                        .any(|s| matches!(s, Seed::Bytes(e) if uses_local(e)))
+                    {
+                        Err(local_in_constraint(c.span))?
+                    }
+                    constraints.bump = Some(Bump::Canonical);
+                    if !seeds.is_empty() {
+                        constraints.seeds = Some(seeds);
+                    }
+                }
+                if method == "deriveWithBump" {
+                    let bump = self.lower_expr(call_arg(c, c.args.len().max(1) - 1)?)?;
+                    if uses_local(&bump) {
+                        Err(local_in_constraint(c.span))?
+                    }
+                    constraints.bump = Some(Bump::Stored(bump));
+                }
+                self.ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints = constraints;
+            }
+            "init" | "initIfNeeded" => {
+                let payer = ident_arg(c, 0)?.to_case(Case::Snake);
+                let space = match &of_type {
+                    AccountType::Custom(ty) => self.program.account(ty).map(|a| a.space),
+                    _ => None,
+                };
+                let mint = match of_type.is_mint() {
+                    true => {
+                        let mint = self.mint_options(call_arg(c, 1)?)?;
+                        if mint.decimals.is_none() || mint.authority.is_none() {
+                            Err(Diagnostic::new(
+                                c.span,
+                                "new mints need `decimals` and an `authority`",
+                            )
+                            .with_hint(
+                                "e.g. `mint.init(payer, { decimals: 6, authority: payer })`",
+                            ))?
+                        }
+                        self.ix.uses_token_program = true;
+                        Some(mint)
+                    }
+                    false => None,
+                };
+                self.ix.uses_system_program = true;
+                let account = self
+                    .ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?;
+                account.constraints.init = Some(Init {
+                    payer,
+                    if_needed: method == "initIfNeeded",
+                    space,
+                });
+                if let Some(mint) = mint {
+                    account.constraints.mint = Some(mint);
+                }
+            }
+            "address" | "owner" => {
+                let key = self.constraint_key(call_arg(c, 0)?)?;
+                let constraints = &mut self
+                    .ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints;
+                match method {
+                    "address" => constraints.address = Some(key),
+                    _ => constraints.owner = Some(key),
+                }
+            }
+            "executable" => {
+                self.ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints
+                    .executable = true;
+            }
+            "constraint" => {
+                let arg = call_arg(c, 0)?;
+                let cond = self.lower_expr(arg)?;
+                if uses_local(&cond) {
+                    Err(local_in_constraint(arg.span()))?
+                }
+                if let Some(ty) = self.type_of(&cond).filter(|ty| ty != "Boolean") {
+                    Err(Diagnostic::new(
+                        arg.span(),
+                        format!("expected a Boolean condition, found `{}`", ty),
+                    ))?
+                }
+                let error = match c.args.get(1) {
+                    Some(arg) => Some(self.raised_error(&arg.expr)?),
+                    None => None,
+                };
+                self.ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints
+                    .constraint
+                    .push(Constraint { cond, error });
+            }
+            "realloc" => {
+                if !matches!(of_type, AccountType::Custom(_)) {
+                    Err(Diagnostic::new(
+                        c.callee.span(),
+                        "only custom accounts can be reallocated",
+                    ))?
+                }
+                self.ix.uses_system_program = true;
+                let payer = ident_arg(c, 0)?.to_case(Case::Snake);
+                let arg = call_arg(c, 1)?;
+                let mut space = self.lower_expr(arg)?;
+                if uses_local(&space) {
+                    Err(local_in_constraint(arg.span()))?
+                }
+                match self.type_of(&space) {
+                    None => {}
+                    Some(ty) if ty == "usize" => {}
+                    Some(ty) if is_integer(&ty) => {
+                        space = Expression::Cast {
+                            expr: Box::new(space),
+                            to: String::from("usize"),
+                        }
+                    }
+                    Some(ty) => Err(Diagnostic::new(
+                        arg.span(),
+                        format!("expected an integer space, found `{}`", ty),
+                    ))?,
+                }
+                let arg = call_arg(c, 2)?;
+                let zero = match arg {
+                    Expr::Lit(Lit::Bool(b)) => b.value,
+                    _ => Err(Diagnostic::new(arg.span(), "expected `true` or `false`")
+                        .with_label("whether the new bytes are zeroed"))?,
+                };
+                let account = self
+                    .ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?;
+                account.constraints.realloc = Some(Realloc { payer, space, zero });
+                account.constraints.is_mut = true;
+            }
+            "close" => {
+                let destination = ident_arg(c, 0)?.to_case(Case::Snake);
+                let account = self
+                    .ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?;
+                account.constraints.close = Some(destination);
+                account.constraints.is_mut = true;
+            }
+            "has" if of_type.is_mint() => {
+                let mint = self.mint_options(call_arg(c, 0)?)?;
+                self.ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints
+                    .mint = Some(mint);
+            }
+            "has" => {
+                let mut has_one: Vec<String> = vec![];
+                for elem in array_arg(c, 0)?.iter().flatten() {
+                    has_one.push(
+                        elem.expr
+                            .as_ident()
+                            .ok_or(PoseidonError::IdentNotFound.at(elem.span()))?
+                            .sym
+                            .to_string()
+                            .to_case(Case::Snake),
+                    );
+                }
+                self.ix
+                    .account_mut(name)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints
+                    .has_one = has_one;
+            }
+            _ => Err(Diagnostic::new(
+                c.callee.span(),
+                format!("unsupported account method `{}`", method),
+            ))?,
+        }
+        Ok(())
+    }
+
+    /// Reads `{ decimals, authority, freezeAuthority }`, where the authorities are accounts
+    /// or their keys.
+    fn mint_options(&mut self, arg: &Expr) -> Result<MintConstraint> {
+        let object = arg.as_object().ok_or(
+            Diagnostic::new(arg.span(), "mint options are given as an object literal")
+                .with_hint("e.g. `{ decimals: 6, authority: owner }`"),
+        )?;
+        let mut mint = MintConstraint::default();
+        for prop in object.props.iter() {
+            let (key, value) = object_prop(prop, "the mint options")?;
+            match key.as_str() {
+                "decimals" => {
+                    let decimals = self.lower_expr(&value)?;
+                    if uses_local(&decimals) {
+                        Err(local_in_constraint(value.span()))?
+                    }
+                    if let Some(ty) = self.type_of(&decimals).filter(|ty| ty != "u8") {
+                        Err(Diagnostic::new(
+                            value.span(),
+                            format!("expected `u8` decimals, found `{}`", ty),
+                        ))?
+                    }
+                    mint.decimals = Some(decimals);
+                }
+                "authority" | "freezeAuthority" => {
+                    let account = match &value {
+                        Expr::Member(m) if m.prop.as_ident().is_some_and(|p| p.sym == "key") => {
+                            &m.obj
+                        }
+                        _ => &value,
+                    };
+                    let account = account
+                        .as_ident()
+                        .ok_or(PoseidonError::IdentNotFound.at(account.span()))?
+                        .sym
+                        .as_ref()
+                        .to_case(Case::Snake);
+                    if self.ix.account(&account).is_none() {
+                        Err(PoseidonError::AccountNotFound(account.clone()).at(value.span()))?
+                    }
+                    match key.as_str() {
+                        "authority" => mint.authority = Some(account),
+                        _ => mint.freeze_authority = Some(account),
+                    }
+                }
+                _ => Err(
+                    Diagnostic::new(prop.span(), format!("unknown mint option `{}`", key))
+                        .with_hint("mints take `decimals`, `authority` and `freezeAuthority`"),
+                )?,
+            }
+        }
+        Ok(mint)
+    }
+
+    /// Lowers the `Pubkey` an account's address or owner is checked against.
+    fn constraint_key(&mut self, arg: &Expr) -> Result<Expression> {
+        let key = self.lower_expr(arg)?;
+        if uses_local(&key) {
+            Err(local_in_constraint(arg.span()))?
+        }
+        match self.type_of(&key) {
+            Some(ty) if ty == "Pubkey" => Ok(key),
+            ty => Err(Diagnostic::new(
+                arg.span(),
+                format!(
+                    "expected a `Pubkey`, found `{}`",
+                    ty.as_deref().unwrap_or("integer")
+                ),
+            )
+            .with_hint("use a `Pubkey` constant, argument or account key"))?,
+        }
+    }
+
+    /// Lowers an argument of a CPI that takes the Poseidon type `expected`, where integer
+    /// literals fit any integer type. Without `expected` any value is passed along.
+    fn cpi_arg(
+        &mut self,
+        arg: &Expr,
+        expected: Option<&str>,
+        passing: ArgPassing,
+    ) -> Result<(Expression, ArgPassing)> {
+        // String constants can't be used in expressions, but are fine where a string is expected
+        let str_constant = arg.as_ident().and_then(|i| {
+            let name = i.sym.as_ref().to_case(Case::Snake);
+            if self.local(&name).is_some() || self.ix.arg(&name).is_some() {
+                return None;
+            }
+            let name = name.to_case(Case::UpperSnake);
+            match self.program.constant(&name)?.value {
+                ConstantValue::Str(_) => Some(name),
+                _ => None,
+            }
+        });
+        let value = match str_constant {
+            Some(name) if expected == Some("Str") => Expression::Const(name),
+            _ => self.lower_expr(arg)?,
+        };
+        let Some(expected) = expected else {
+            return Ok((value, passing));
+        };
+        match self.type_of(&value) {
+            Some(ty) if ty == expected => Ok((value, passing)),
+            None if is_integer(expected) => Ok((value, passing)),
+            ty => Err(Diagnostic::new(
+                arg.span(),
+                format!(
+                    "expected a `{}`, found `{}`",
+                    expected,
+                    ty.as_deref().unwrap_or("integer")
+                ),
+            )
+            .with_hint(match expected {
+                "Pubkey" => String::from("use a `Pubkey` constant, argument or account key"),
+                "Str" => String::from("use a `Str` constant or argument"),
+                _ => format!("use a `{}` value", expected),
+            }))?,
+        }
+    }
+
+    fn lower_cpi(&mut self, program: CpiProgram, method: &str, c: &CallExpr) -> Result<CpiCall> {
+        let (program_name, signatures): (&str, &[CpiSignature]) = match program {
+            CpiProgram::System => ("SystemProgram", &SYSTEM_PROGRAM_CPIS),
+            CpiProgram::Token | CpiProgram::TokenInterface => ("TokenProgram", &TOKEN_PROGRAM_CPIS),
+            CpiProgram::AssociatedToken => {
+                ("AssociatedTokenProgram", &ASSOCIATED_TOKEN_PROGRAM_CPIS)
+            }
+            CpiProgram::External(_) => {
+                Err(anyhow!("external programs are invoked from their IDL"))?
+            }
+        };
+        let signature = signatures
+            .iter()
+            .find(|s| s.name == method)
+            .ok_or(Diagnostic::new(
+                c.callee.span(),
+                format!("`{}.{}` is not supported", program_name, method),
+            ))?;
+        if program == CpiProgram::TokenInterface && method == "transfer" {
+            Err(Diagnostic::new(
+                c.callee.span(),
+                "`TokenProgram.transfer` is not supported with Token-2022 accounts",
+            )
+            .with_label("Token-2022 deprecates plain transfers")
+            .with_hint(
+                "use `TokenProgram.transferChecked`, which also takes the mint and its decimals",
+            ))?
+        }
+        let mut accounts: Vec<(String, String)> = vec![];
+        for (index, field) in signature.accounts.iter().enumerate() {
+            let acc = ident_arg(c, index)?.to_case(Case::Snake);
+            if self.ix.account(&acc).is_none() {
+                Err(PoseidonError::AccountNotFound(acc.clone()).at(c.args[index].span()))?
+            }
+            self.check_present(&acc, c.args[index].span())?;
+            accounts.push((field.to_string(), acc));
+        }
+        let mut args: Vec<(Expression, ArgPassing)> = vec![];
+        for (index, kind) in signature.args.iter().enumerate() {
+            let arg = call_arg(c, signature.accounts.len() + index)?;
+            let (expected, passing) = match kind {
+                CpiArg::Int => (None, ArgPassing::Value),
+                CpiArg::Pubkey => (Some("Pubkey"), ArgPassing::Ref),
+                CpiArg::Str => (Some("Str"), ArgPassing::Ref),
+                CpiArg::String => (Some("Str"), ArgPassing::Owned),
+            };
+            let (value, passing) = self.cpi_arg(arg, expected, passing)?;
+            if let (CpiArg::Int, Some(ty)) = (kind, self.type_of(&value)) {
+                if !is_integer(&ty) {
+                    Err(Diagnostic::new(
+                        arg.span(),
+                        format!("expected an integer, found `{}`", ty),
+                    )
+                    .with_hint("use an integer value, e.g. a `u64` argument"))?
+                }
+            }
+            args.push((value, passing));
+        }
+        let seeds_index = signature.accounts.len() + signature.args.len();
+        let signer = accounts[signature.signer].1.clone();
+        let signer_seeds = self.signer_seeds(c, seeds_index, &[signer])?;
+        match program {
+            CpiProgram::System => {
+                self.ix.uses_system_program = true;
+                // Every account but `base` has its lamports, data or owner changed
+                for (_, account) in accounts.iter().filter(|(field, _)| field != "base") {
+                    self.ix
+                        .account_mut(account)
+                        .ok_or(anyhow!("account not found"))?
+                        .constraints
+                        .is_mut = true;
+                }
+            }
+            CpiProgram::Token | CpiProgram::TokenInterface => self.ix.uses_token_program = true,
+            CpiProgram::AssociatedToken => {
+                self.ix.uses_associated_token_program = true;
+                self.ix.uses_token_program = true;
+                self.ix.uses_system_program = true;
+                // The new account is written to, and is created by the programs passed along
+                let associated_token = &accounts[1].1;
+                self.ix
+                    .account_mut(associated_token)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints
+                    .is_mut = true;
+                for program in [BuiltinProgram::System, BuiltinProgram::Token] {
+                    let name = program.account_name().to_string();
+                    accounts.push((name.clone(), name));
+                }
+            }
+            CpiProgram::External(_) => {}
+        }
+        Ok(CpiCall {
+            program,
+            instruction: signature.instruction.to_string(),
+            accounts,
+            optional_accounts: vec![],
+            args,
+            signer_seeds,
+        })
+    }
+
+    /// `Lending.deposit({ user, vault }, { amount }, seeds?)`, checked against the IDL of the
+    /// program. The arguments object is left out for instructions without any.
+    fn lower_external_cpi(
+        &mut self,
+        import: &IdlImport,
+        method: &str,
+        c: &CallExpr,
+    ) -> Result<CpiCall> {
+        let callee = format!("{}.{}", import.name, method);
+        let instruction = import
+            .idl
+            .instructions
+            .iter()
+            .find(|ix| ix.name == method.to_case(Case::Snake))
+            .ok_or(
+                Diagnostic::new(
+                    c.callee.span(),
+                    format!("`{}` is not an instruction of `{}`", method, import.name),
+                )
+                .with_hint(format!(
+                    "its IDL has {}",
+                    import
+                        .idl
+                        .instructions
+                        .iter()
+                        .map(|ix| format!("`{}`", ix.name.to_case(Case::Camel)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            )?;
+
+        let arg = call_arg(c, 0)?;
+        let object = arg.as_object().ok_or(
+            Diagnostic::new(
+                arg.span(),
+                "the accounts of a CPI are given as an object literal",
+            )
+            .with_hint("e.g. `{ user: owner, vault }`"),
+        )?;
+        let mut passed: Vec<(String, String)> = vec![];
+        for prop in object.props.iter() {
+            let (key, value) = object_prop(prop, &format!("the accounts of `{}`", callee))?;
+            let field = key.to_case(Case::Snake);
+            if !instruction
+                .accounts
+                .iter()
+                .any(|a| account_item_name(a) == field)
+            {
+                Err(Diagnostic::new(
+                    prop.span(),
+                    format!("`{}` has no account `{}`", callee, key),
+                ))?
+            }
+            let account = value
+                .as_ident()
+                .ok_or(PoseidonError::IdentNotFound.at(value.span()))?
+                .sym
+                .as_ref()
+                .to_case(Case::Snake);
+            if self.ix.account(&account).is_none() {
+                Err(PoseidonError::AccountNotFound(account.clone()).at(value.span()))?
+            }
+            self.check_present(&account, value.span())?;
+            passed.push((field, account));
+        }
+        let mut accounts: Vec<(String, String)> = vec![];
+        let mut optional_accounts: Vec<(String, Option<String>)> = vec![];
+        let mut signers: Vec<String> = vec![];
+        for item in instruction.accounts.iter() {
+            let IdlInstructionAccountItem::Single(idl_account) = item else {
+                Err(Diagnostic::new(
+                    c.span,
+                    format!("`{}` takes nested accounts, which aren't supported", callee),
+                ))?
+            };
+            let account = passed
+                .iter()
+                .find(|(field, _)| *field == idl_account.name)
+                .map(|(_, account)| account.clone());
+            let Some(account) = account else {
+                if idl_account.optional {
+                    optional_accounts.push((idl_account.name.clone(), None));
+                } else if let Some(program) = self.builtin_program(idl_account.address.as_deref()) {
+                    let name = program.account_name().to_string();
+                    accounts.push((idl_account.name.clone(), name));
+                } else {
+                    Err(Diagnostic::new(
+                        arg.span(),
+                        format!(
+                            "missing account `{}` for `{}`",
+                            idl_account.name.to_case(Case::Camel),
+                            callee
+                        ),
+                    ))?
+                }
+                continue;
+            };
+            if idl_account.writable {
+                self.ix
+                    .account_mut(&account)
+                    .ok_or(anyhow!("account not found"))?
+                    .constraints
+                    .is_mut = true;
+            }
+            if idl_account.signer {
+                signers.push(account.clone());
+            }
+            match idl_account.optional {
+                true => optional_accounts.push((idl_account.name.clone(), Some(account))),
+                false => accounts.push((idl_account.name.clone(), account)),
+            }
+        }
+
+        // The arguments object is optional for instructions without any
+        let takes_args =
+            !instruction.args.is_empty() || c.args.get(1).is_some_and(|a| a.expr.is_object());
+        let mut values: Vec<(String, Expr)> = vec![];
+        if takes_args {
+            let arg = call_arg(c, 1)?;
+            let object = arg.as_object().ok_or(
+                Diagnostic::new(
+                    arg.span(),
+                    "the arguments of a CPI are given as an object literal",
+                )
+                .with_hint("e.g. `{ amount: 10 }`"),
+            )?;
+            for prop in object.props.iter() {
+                let (key, value) = object_prop(prop, &format!("the arguments of `{}`", callee))?;
+                if !instruction
+                    .args
+                    .iter()
+                    .any(|a| a.name == key.to_case(Case::Snake))
+                {
+                    Err(Diagnostic::new(
+                        prop.span(),
+                        format!("`{}` has no argument `{}`", callee, key),
+                    ))?
+                }
+                values.push((key.to_case(Case::Snake), value));
+            }
+        }
+        let mut args: Vec<(Expression, ArgPassing)> = vec![];
+        for idl_arg in instruction.args.iter() {
+            let value = values
+                .iter()
+                .find(|(name, _)| *name == idl_arg.name)
+                .map(|(_, value)| value)
+                .ok_or(Diagnostic::new(
+                    call_arg(c, 1)?.span(),
+                    format!(
+                        "missing argument `{}` for `{}`",
+                        idl_arg.name.to_case(Case::Camel),
+                        callee
+                    ),
+                ))?;
+            let expected = poseidon_type(&idl_arg.ty).ok_or(
+                Diagnostic::new(
+                    value.span(),
+                    format!(
+                        "argument `{}` has a type Poseidon can't pass to other programs yet",
+                        idl_arg.name.to_case(Case::Camel)
+                    ),
+                )
+                .with_hint("integers, `Boolean`, `Pubkey` and `Str` arguments are supported"),
+            )?;
+            let passing = match expected {
+                "Str" => ArgPassing::Owned,
+                _ => ArgPassing::Value,
+            };
+            args.push(self.cpi_arg(value, Some(expected), passing)?);
+        }
+
+        let seeds_index = if takes_args { 2 } else { 1 };
+        let signer_seeds = self.signer_seeds(c, seeds_index, &signers)?;
+        let program = ExternalProgram {
+            crate_name: import.idl.metadata.name.clone(),
+            version: import.idl.metadata.version.clone(),
+            address: import.idl.address.clone(),
+            poseidon: import.idl.metadata.description.as_deref() == Some(idl::DESCRIPTION),
+        };
+        if !self.ix.external_programs.contains(&program) {
+            self.ix.external_programs.push(program.clone());
+        }
+        Ok(CpiCall {
+            program: CpiProgram::External(program),
+            instruction: instruction.name.clone(),
+            accounts,
+            optional_accounts,
+            args,
+            signer_seeds,
+        })
+    }
+
+    /// The builtin program at `address`, which is passed to CPIs without being asked for.
+    fn builtin_program(&mut self, address: Option<&str>) -> Option<BuiltinProgram> {
+        let program = [
+            BuiltinProgram::System,
+            BuiltinProgram::Token,
+            BuiltinProgram::AssociatedToken,
+        ]
+        .into_iter()
+        .find(|p| address.is_some() && p.address() == address)?;
+        match program {
+            BuiltinProgram::System => self.ix.uses_system_program = true,
+            BuiltinProgram::AssociatedToken => self.ix.uses_associated_token_program = true,
+            _ => self.ix.uses_token_program = true,
+        }
+        Some(program)
+    }
+
+    /// Lowers the seeds a CPI is signed with, passed as the `index`th argument. They are
+    /// required when one of the `signers` is a PDA.
+    fn signer_seeds(
+        &mut self,
+        c: &CallExpr,
+        index: usize,
+        signers: &[String],
+    ) -> Result<Option<Vec<Seed>>> {
+        let signer_is_pda = signers.iter().any(|signer| {
+            self.ix
+                .account(signer)
+                .is_some_and(|a| a.constraints.seeds.is_some())
+        });
+        if !signer_is_pda && c.args.len() <= index {
+            return Ok(None);
+        }
+        let seeds = self.seeds_of(seeds_arg(c, index)?)?;
+        Ok(Some(self.lower_seeds(&seeds, true)?))
+    }
+
+    fn lower_seeds(
+        &mut self,
+        seeds: &[Option<ExprOrSpread>],
+        is_signer_seeds: bool,
+    ) -> Result<Vec<Seed>> {
+        let mut lowered: Vec<Seed> = vec![];
+        let elems: Vec<&ExprOrSpread> = seeds.iter().flatten().collect();
+        for (index, elem) in elems.iter().enumerate() {
+            let is_last = index + 1 == elems.len();
+            let seed = match &*elem.expr {
+                Expr::Lit(Lit::Str(seedstr)) => Seed::Str(seedstr.value.to_string()),
+                // String constants are spelled out, like the literals they stand for
+                Expr::Ident(i) => match self
+                    .program
+                    .constant(&i.sym.as_ref().to_case(Case::UpperSnake))
+                    .map(|c| &c.value)
+                {
+                    Some(ConstantValue::Str(s)) => Seed::Str(s.clone()),
+                    _ => Err(unsupported_seed(elem.span()))?,
+                },
+                Expr::Member(m) => {
+                    let (obj, prop) = member_names(m)?;
+                    if prop == "key" {
+                        let account = obj.to_case(Case::Snake);
+                        self.check_present(&account, m.obj.span())?;
+                        Seed::AccountKey(account)
+                    } else if is_signer_seeds && is_last {
+                        Seed::Bump(self.lower_expr(&elem.expr)?)
+                    } else {
+                        Err(unsupported_seed(elem.span()))?
+                    }
+                }
+                Expr::Call(c) => {
+                    let members = c
+                        .callee
+                        .as_expr()
+                        .and_then(|e| e.as_member())
+                        .ok_or(unsupported_seed(elem.span()))?;
+                    let prop = members
+                        .prop
+                        .as_ident()
+                        .ok_or(PoseidonError::IdentNotFound.at(members.prop.span()))?
+                        .sym
+                        .as_ref();
+                    match prop {
+                        "toBytes" => {
+                            let e = self.lower_expr(&members.obj)?;
+                            // `bump.toBytes()` closing signer seeds is the bump itself
+                            if is_signer_seeds
+                                && is_last
+                                && self.type_of(&e).as_deref() == Some("u8")
+                            {
+                                Seed::Bump(e)
+                            } else {
+                                Seed::Bytes(e)
+                            }
+                        }
+                        "getBump" if is_signer_seeds && is_last => {
+                            Seed::Bump(self.lower_expr(&elem.expr)?)
+                        }
+                        _ => Err(unsupported_seed(elem.span()))?,
+                    }
+                }
+                _ => Err(unsupported_seed(elem.span()))?,
+            };
+            lowered.push(seed);
+        }
+        if is_signer_seeds && !matches!(lowered.last(), Some(Seed::Bump(_))) {
+            let span = elems.last().map(|elem| elem.span()).unwrap_or_default();
+            Err(PoseidonError::BumpNotFound.at(span))?
+        }
+        Ok(lowered)
+    }
+
+    fn lower_expr(&mut self, expr: &Expr) -> Result<Expression> {
+        match expr {
+            Expr::Lit(lit) => lower_lit(lit),
+            // `new i64(0)` only gives the literal a Poseidon type
+            Expr::New(exp) => {
+                let value =
+                    exp.args
+                        .as_ref()
+                        .and_then(|args| args.first())
+                        .ok_or(Diagnostic::new(
+                            exp.span,
+                            "need some value in new expression",
+                        ))?;
+                let value = self.lower_expr(&value.expr)?;
+                let of_type = exp.callee.as_ident().map(|i| i.sym.to_string());
+                Ok(self.coerce(value, of_type))
+            }
+            Expr::Ident(i) => {
+                let name = i.sym.as_ref().to_case(Case::Snake);
+                if let Some(local) = self.local(&name) {
+                    if local.seeds.is_some() {
+                        Err(Diagnostic::new(
+                            i.span,
+                            format!("`{}` can only be used as seeds", i.sym),
+                        ))?
+                    }
+                    Ok(Expression::Local(name))
+                } else if self.ix.arg(&name).is_some() {
+                    self.check_present(&name, i.span)?;
+                    Ok(Expression::Arg(name))
+                } else if let Some(constant) = self
+                    .program
+                    .constant(&i.sym.as_ref().to_case(Case::UpperSnake))
+                {
+                    if let ConstantValue::Str(_) = constant.value {
+                        Err(Diagnostic::new(
+                            i.span,
+                            format!("`{}` is a string and can only be used as a seed", i.sym),
+                        ))?
+                    }
+                    Ok(Expression::Const(constant.name.clone()))
+                } else {
+                    Err(
+                        Diagnostic::new(i.span, format!("cannot find value `{}`", i.sym))
+                            .with_hint(
+                                "only locals, constants and instruction arguments can be used as values",
+                            )
+                            .into(),
+                    )
+                }
+            }
+            Expr::Member(m) => {
+                let (obj, prop) = member_names(m)?;
+                if let Some(e) = self.program.enumeration(obj) {
+                    let variant = prop.to_case(Case::Pascal);
+                    if !e.variants.contains(&variant) {
+                        Err(Diagnostic::new(
+                            m.prop.span(),
+                            format!("`{}` has no variant `{}`", obj, prop),
+                        ))?
+                    }
+                    return Ok(Expression::Variant {
+                        of_type: e.name.clone(),
+                        variant,
+                    });
+                }
+                let account = obj.to_case(Case::Snake);
+                if self.ix.account(&account).is_none() {
+                    Err(PoseidonError::AccountNotFound(obj.to_string()).at(m.obj.span()))?
+                }
+                self.check_present(&account, m.obj.span())?;
+                if prop == "key" {
+                    Ok(Expression::AccountKey(account))
+                } else {
+                    Ok(Expression::AccountField {
+                        account,
+                        field: prop.to_case(Case::Snake),
+                    })
+                }
+            }
+            Expr::Call(c) => {
+                let members = c
+                    .callee
+                    .as_expr()
+                    .and_then(|e| e.as_member())
+                    .ok_or(unsupported_expr(c.span))?;
+                let method = members
+                    .prop
+                    .as_ident()
+                    .ok_or(PoseidonError::IdentNotFound.at(members.prop.span()))?
+                    .sym
+                    .as_ref();
+                if method == "getBump" {
+                    let account = members
+                        .obj
+                        .as_ident()
+                        .ok_or(PoseidonError::IdentNotFound.at(members.obj.span()))?
+                        .sym
+                        .as_ref()
+                        .to_case(Case::Snake);
+                    match self.ix.account(&account) {
+                        None => {
+                            Err(PoseidonError::AccountNotFound(account.clone())
+                                .at(members.obj.span()))?
+                        }
+                        Some(a) if a.optional => Err(Diagnostic::new(
+                            members.obj.span(),
+                            "the bump of an optional account is not known",
+                        ))?,
+                        Some(_) => {}
+                    }
+                    return Ok(Expression::Bump(account));
+                }
+                let receiver = self.lower_expr(&members.obj)?;
+                if method == "toBytes" {
+                    return Ok(Expression::ToBytes(Box::new(receiver)));
+                }
+                let (op, mode) = BinaryOp::from_method(method)
+                    .map(|op| (op, ArithmeticMode::Checked))
+                    .or(ArithmeticMode::from_method(method))
+                    .ok_or(Diagnostic::new(
+                        members.prop.span(),
+                        format!("unsupported method `{}`", method),
+                    ))?;
+                let operand = self.lower_expr(call_arg(c, 0)?)?;
+                Ok(self.binary(op, mode, receiver, operand))
+            }
+            Expr::Bin(b) => {
+                let op = binary_op(b.op).ok_or(
+                    Diagnostic::new(b.span, format!("operator `{}` is not supported", b.op))
+                        .with_hint("use `===` and `!==` for comparisons"),
+                )?;
+                let left = self.lower_expr(&b.left)?;
+                let right = self.lower_expr(&b.right)?;
+                Ok(self.binary(op, ArithmeticMode::Checked, left, right))
+            }
+            Expr::Unary(u) => {
+                let operand = self.lower_expr(&u.arg)?;
+                match (u.op, operand) {
+                    (UnaryOp::Minus, Expression::Int(i)) => Ok(Expression::Int(-i)),
+                    (UnaryOp::Minus, operand) => Ok(Expression::Unary {
+                        op: ir::UnaryOp::Neg,
+                        expr: Box::new(operand),
+                    }),
+                    (UnaryOp::Bang, operand) => Ok(Expression::Unary {
+                        op: ir::UnaryOp::Not,
+                        expr: Box::new(operand),
+                    }),
+                    (UnaryOp::Plus, operand) => Ok(operand),
+                    _ => Err(unsupported_expr(u.span).into()),
+                }
+            }
+            Expr::Paren(p) => self.lower_expr(&p.expr),
+            _ => Err(unsupported_expr(expr.span()).into()),
+        }
+    }
+
+    /// Resolves the error thrown by `throw` or `require` to its `ErrorCode` variant.
+    fn raised_error(&self, e: &Expr) -> Result<String> {
+        let name = error_name(e)?.to_case(Case::Pascal);
+        if self.program.error(&name).is_some() {
+            return Ok(name);
+        }
+        let type_enum = match e {
+            Expr::Member(m) => m
+                .obj
+                .as_ident()
+                .and_then(|i| self.program.enumeration(i.sym.as_ref())),
+            _ => None,
+        };
+        let hint = match type_enum {
+            Some(type_enum) => format!(
+                "`{}` is read as a type as none of its variants has a message, declare errors as `{} = \"message\"`",
+                type_enum.name, name
+            ),
+            None => String::from(
+                "declare it in an exported `enum` or a class that extends `ProgramError`",
+            ),
+        };
+        Err(Diagnostic::new(e.span(), format!("cannot find error `{}`", name)).with_hint(hint))?
+    }
+
+    /// Builds `left op right`, casting `right` to the type of `left` like the Poseidon
+    /// numeric methods do when both sides have a known integer type. Arithmetic is
+    /// checked unless another `mode` is asked for.
+    fn binary(
+        &mut self,
+        op: BinaryOp,
+        mode: ArithmeticMode,
+        left: Expression,
+        right: Expression,
+    ) -> Expression {
+        let right = match op {
+            BinaryOp::Shl | BinaryOp::Shr | BinaryOp::And | BinaryOp::Or => right,
+            _ => self.coerce(right, self.type_of(&left)),
+        };
+        if !op.is_arithmetic() {
+            return Expression::Binary {
+                op,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+        let left = match (&left, &right) {
+            (Expression::Int(l), Expression::Int(r)) => {
+                if let Some(folded) = fold(op, *l, *r) {
+                    return Expression::Int(folded);
+                }
+                left
+            }
+            // `1.checked_add(x)` needs the literal's type spelled out
+            (Expression::Int(_), _) => {
+                let of_type = self.type_of(&right);
+                match of_type {
+                    Some(to) if is_integer(&to) => Expression::Cast {
+                        expr: Box::new(left),
+                        to,
+                    },
+                    _ => left,
+                }
+            }
+            _ => left,
+        };
+        if mode == ArithmeticMode::Checked {
+            self.ix.uses_checked_arithmetic = true;
+        }
+        Expression::Arithmetic {
+            op,
+            mode,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// `e` where it's moved into a local, a field or an event, cloned when it names a value that
+    /// isn't `Copy` and is still readable afterwards.
+    fn owned(&self, e: Expression) -> Expression {
+        match e {
+            Expression::Arg(_) | Expression::Local(_) | Expression::AccountField { .. }
+                if self
+                    .type_of(&e)
+                    .is_some_and(|ty| !self.program.is_copy(&ty)) =>
+            {
+                Expression::Clone(Box::new(e))
+            }
+            _ => e,
+        }
+    }
+
+    /// Casts `e` to `to` if both are integers of different widths.
+    fn coerce(&self, e: Expression, to: Option<String>) -> Expression {
+        match (self.type_of(&e), to) {
+            (Some(from), Some(to)) if from != to && is_integer(&from) && is_integer(&to) => {
+                Expression::Cast {
+                    expr: Box::new(e),
+                    to,
+                }
+            }
+            _ => e,
+        }
+    }
+
+    /// The Poseidon type of `e`, if it can be told without a type checker.
+    fn type_of(&self, e: &Expression) -> Option<String> {
+        match e {
+            Expression::Int(_) => None,
+            Expression::Bool(_) => Some(String::from("Boolean")),
+            Expression::Arg(name) => self.ix.arg(name).map(|a| a.of_type.clone()),
+            Expression::Local(name) => self.local(name).map(|l| l.of_type.clone()),
+            Expression::Const(name) => self.program.constant(name).map(|c| c.of_type.clone()),
+            Expression::Variant { of_type, .. } => Some(of_type.clone()),
+            Expression::AccountKey(_) => Some(String::from("Pubkey")),
+            Expression::AccountField { account, field } => {
+                match &self.ix.account(account)?.of_type {
+                    AccountType::Custom(ty) => self
+                        .program
+                        .account(ty)?
+                        .fields
+                        .iter()
+                        .find(|f| &f.name == field)
+                        .map(|f| f.of_type.clone()),
+                    _ => None,
+                }
+            }
+            Expression::Bump(_) => Some(String::from("u8")),
+            Expression::Binary { op, left, right } => match op {
+                BinaryOp::Eq
+                | BinaryOp::Ne
+                | BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge
+                | BinaryOp::And
+                | BinaryOp::Or => Some(String::from("Boolean")),
+                _ => self.type_of(left).or_else(|| self.type_of(right)),
+            },
+            Expression::Arithmetic { left, right, .. } => {
+                self.type_of(left).or_else(|| self.type_of(right))
+            }
+            Expression::Unary { op, expr } => match op {
+                ir::UnaryOp::Not if self.type_of(expr).as_deref() == Some("Boolean") => {
+                    Some(String::from("Boolean"))
+                }
+                _ => self.type_of(expr),
+            },
+            Expression::Cast { to, .. } => Some(to.clone()),
+            Expression::ToBytes(_) => None,
+            Expression::Clone(e) => self.type_of(e),
+        }
+    }
+}
+
+/// Whether `stmts` assign a field of `account`, looking into nested blocks.
+fn assigns_to(stmts: &[Statement], account: &str) -> bool {
+    stmts.iter().any(|s| match s {
+        Statement::Assign {
+            target: Expression::AccountField { account: a, .. },
+            ..
+        } => a == account,
+        Statement::If {
+            then, otherwise, ..
+        }
+        | Statement::IfSome {
+            then, otherwise, ..
+        } => {
+            assigns_to(then, account)
+                || otherwise.as_deref().is_some_and(|o| assigns_to(o, account))
+        }
+        Statement::Block(stmts) => assigns_to(stmts, account),
+        Statement::Match {
+            arms, otherwise, ..
+        } => {
+            arms.iter().any(|(_, body)| assigns_to(body, account))
+                || otherwise.as_deref().is_some_and(|o| assigns_to(o, account))
+        }
+        _ => false,
+    })
+}
+
+/// Whether `e` reads a local, which doesn't exist yet when the accounts are validated.
+fn uses_local(e: &Expression) -> bool {
+    match e {
+        Expression::Local(_) => true,
+        Expression::Binary { left, right, .. } | Expression::Arithmetic { left, right, .. } => {
+            uses_local(left) || uses_local(right)
+        }
+        Expression::Unary { expr, .. } | Expression::Cast { expr, .. } => uses_local(expr),
+        Expression::ToBytes(e) | Expression::Clone(e) => uses_local(e),
+        _ => false,
+    }
+}
+
+fn local_in_constraint(span: Span) -> Diagnostic {
+    Diagnostic::new(span, "account constraints cannot use locals")
+        .with_label("locals are only known once the instruction runs")
+        .with_hint("use instruction arguments or account fields instead")
+}
+
+fn account_item_name(item: &IdlInstructionAccountItem) -> &str {
+    match item {
+        IdlInstructionAccountItem::Single(account) => &account.name,
+        IdlInstructionAccountItem::Composite(accounts) => &accounts.name,
+    }
+}
+
+/// The Poseidon type of an argument of another program's instruction, for the IDL types
+/// that can be passed to it.
+fn poseidon_type(ty: &IdlType) -> Option<&'static str> {
+    Some(match ty {
+        IdlType::Bool => "Boolean",
+        IdlType::U8 => "u8",
+        IdlType::I8 => "i8",
+        IdlType::U16 => "u16",
+        IdlType::I16 => "i16",
+        IdlType::U32 => "u32",
+        IdlType::I32 => "i32",
+        IdlType::U64 => "u64",
+        IdlType::I64 => "i64",
+        IdlType::U128 => "u128",
+        IdlType::I128 => "i128",
+        IdlType::Pubkey => "Pubkey",
+        IdlType::String => "Str",
+        _ => return None,
+    })
+}
+
+fn token_authority(c: &CallExpr, index: usize) -> Result<String> {
+    let arg = call_arg(c, index)?;
+    let members = arg
+        .as_member()
+        .ok_or(PoseidonError::MemberNotFound.at(arg.span()))?;
+    let (authority, _) = member_names(members)?;
+    Ok(authority.to_case(Case::Snake))
+}
+
+fn lower_lit(lit: &Lit) -> Result<Expression> {
+    match lit {
+        Lit::Num(num) if num.value.fract() == 0.0 => Ok(Expression::Int(integer(num)?)),
+        Lit::Num(num) => Err(
+            Diagnostic::new(num.span, "fractional numbers are not supported")
+                .with_hint("Solana programs only do integer arithmetic")
+                .into(),
+        ),
+        Lit::BigInt(big) => {
+            Ok(Expression::Int(big.value.to_string().parse().map_err(
+                |_| Diagnostic::new(big.span, "integer literal is too large"),
+            )?))
+        }
+        Lit::Bool(b) => Ok(Expression::Bool(b.value)),
+        _ => Err(unsupported_expr(lit.span()).into()),
+    }
+}
+
+/// The value of an integer literal, read from its source text since its `f64` value
+/// is rounded above 2^53.
+fn integer(num: &Number) -> Result<i128, Diagnostic> {
+    let too_large = || Diagnostic::new(num.span, "integer literal is too large");
+    let Some(raw) = num.raw.as_ref().map(|raw| raw.replace('_', "")) else {
+        return Ok(num.value as i128);
+    };
+    let (digits, radix) = match raw.get(..2) {
+        Some("0x" | "0X") => (&raw[2..], 16),
+        Some("0o" | "0O") => (&raw[2..], 8),
+        Some("0b" | "0B") => (&raw[2..], 2),
+        _ => (raw.as_str(), 10),
+    };
+    if radix == 10 && !digits.bytes().all(|b| b.is_ascii_digit()) {
+        // An exponent like `1e9`, exact as long as it's within the integers an `f64` holds
+        return match num.value.abs() <= (1u64 << 53) as f64 {
+            true => Ok(num.value as i128),
+            false => Err(too_large().with_hint("write large integers out in full")),
+        };
+    }
+    i128::from_str_radix(digits, radix)
+        .map_err(|_| too_large().with_hint("integer literals have to fit in an `i128`"))
+}
+
+/// Evaluates arithmetic on two literals, unless it would overflow at runtime anyway.
+fn fold(op: BinaryOp, left: i128, right: i128) -> Option<i128> {
+    match op {
+        BinaryOp::Add => left.checked_add(right),
+        BinaryOp::Sub => left.checked_sub(right),
+        BinaryOp::Mul => left.checked_mul(right),
+        BinaryOp::Div => left.checked_div(right),
+        BinaryOp::Rem => left.checked_rem(right),
+        _ => None,
+    }
+}
+
+fn is_integer(ty: &str) -> bool {
+    matches!(
+        ty,
+        "u8" | "i8"
+            | "u16"
+            | "i16"
+            | "u32"
+            | "i32"
+            | "u64"
+            | "i64"
+            | "u128"
+            | "i128"
+            | "usize"
+            | "isize"
+    )
+}
+
+/// Maps the native TypeScript operators onto the operations of the Poseidon types.
+fn binary_op(op: swc_ecma_ast::BinaryOp) -> Option<BinaryOp> {
+    use swc_ecma_ast::BinaryOp as Ts;
+    match op {
+        Ts::Add => Some(BinaryOp::Add),
+        Ts::Sub => Some(BinaryOp::Sub),
+        Ts::Mul => Some(BinaryOp::Mul),
+        Ts::Div => Some(BinaryOp::Div),
+        Ts::Mod => Some(BinaryOp::Rem),
+        Ts::LShift => Some(BinaryOp::Shl),
+        Ts::RShift => Some(BinaryOp::Shr),
+        Ts::BitAnd => Some(BinaryOp::BitAnd),
+        Ts::BitOr => Some(BinaryOp::BitOr),
+        Ts::BitXor => Some(BinaryOp::BitXor),
+        Ts::EqEqEq => Some(BinaryOp::Eq),
+        Ts::NotEqEq => Some(BinaryOp::Ne),
+        Ts::Lt => Some(BinaryOp::Lt),
+        Ts::LtEq => Some(BinaryOp::Le),
+        Ts::Gt => Some(BinaryOp::Gt),
+        Ts::GtEq => Some(BinaryOp::Ge),
+        Ts::LogicalAnd => Some(BinaryOp::And),
+        Ts::LogicalOr => Some(BinaryOp::Or),
+        _ => None,
+    }
+}
+
+fn assign_op(op: AssignOp) -> Option<BinaryOp> {
+    match op {
+        AssignOp::AddAssign => Some(BinaryOp::Add),
+        AssignOp::SubAssign => Some(BinaryOp::Sub),
+        AssignOp::MulAssign => Some(BinaryOp::Mul),
+        AssignOp::DivAssign => Some(BinaryOp::Div),
+        AssignOp::ModAssign => Some(BinaryOp::Rem),
+        AssignOp::LShiftAssign => Some(BinaryOp::Shl),
+        AssignOp::RShiftAssign => Some(BinaryOp::Shr),
+        AssignOp::BitAndAssign => Some(BinaryOp::BitAnd),
+        AssignOp::BitOrAssign => Some(BinaryOp::BitOr),
+        AssignOp::BitXorAssign => Some(BinaryOp::BitXor),
+        _ => None,
+    }
+}
+
+fn unsupported_seed(span: Span) -> Diagnostic {
+    Diagnostic::new(span, "unsupported seed").with_hint(
+        "seeds can be string literals, `account.key`, `arg.toBytes()` or `account.field.toBytes()`",
+    )
+}
+
+fn unsupported_expr(span: Span) -> Diagnostic {
+    Diagnostic::new(span, "unsupported expression")
+}
+
+fn unsupported_stmt(span: Span) -> Diagnostic {
+    Diagnostic::new(span, "unsupported statement").with_hint(
+        "instruction bodies may contain account calls, assignments, `if`/`else`, `require`, `throw` and `return`",
+    )
+}
+
+/// Reads the error of `throw new MyError()` or `require(cond, MyError.Name)`.
+fn error_name(e: &Expr) -> Result<String> {
+    let name = match e {
+        Expr::Ident(i) => Some(&i.sym),
+        Expr::New(n) => n.callee.as_ident().map(|i| &i.sym),
+        Expr::Member(m) => m.prop.as_ident().map(|i| &i.sym),
+        Expr::Paren(p) => return error_name(&p.expr),
+        _ => None,
+    };
+    Ok(name
+        .ok_or(
+            Diagnostic::new(e.span(), "expected a program error")
+                .with_hint("pass an error like `MyError` or `throw new MyError()`"),
+        )?
+        .to_string())
+}
+
+/// Whether `interface` extends the Poseidon type called `base`.
+fn extends(interface: &TsInterfaceDecl, base: &str) -> bool {
+    matches!(
+        interface.extends.first(),
+        Some(TsExprWithTypeArgs { expr, .. }) if expr.as_ident().is_some_and(|i| i.sym == base)
+    )
+}
+
+/// Reads the fields of an interface, each along with the span of its type.
+fn interface_fields(
+    program: &ProgramModule,
+    interface: &TsInterfaceDecl,
+) -> Result<Vec<(ProgramAccountField, Span)>> {
+    let mut fields = vec![];
+    for f in interface.body.body.iter() {
+        let field = f
+            .as_ts_property_signature()
+            .ok_or(PoseidonError::InvalidProperty.at(f.span()))?;
+        let field_name = field
+            .key
+            .as_ident()
+            .ok_or(PoseidonError::InvalidProperty.at(field.key.span()))?
+            .sym
+            .to_string();
+        let binding = field
+            .type_ann
+            .as_ref()
+            .ok_or(PoseidonError::TypeAnnotationNotFound.at(field.span))?;
+        let (field_type, max_len) = extract_type(binding)?;
+        program
+            .check_type(&field_type)
+            .map_err(|e| Diagnostic::from(e).with_span(binding.span))?;
+        fields.push((
+            ProgramAccountField {
+                name: field_name.to_case(Case::Snake),
+                of_type: field_type,
+                max_len,
+            },
+            binding.span,
+        ));
+    }
+    Ok(fields)
+}
+
+/// Adds up the space the fields take, which needs the length of every `Str` and `Vec`.
+fn fields_space(program: &ProgramModule, fields: &[(ProgramAccountField, Span)]) -> Result<u32> {
+    let mut space = 0;
+    for (field, span) in fields {
+        space += program
+            .space(&field.of_type, &field.max_len)
+            .map_err(|e| e.or_span(*span))?;
+    }
+    Ok(space)
+}
+
+impl ProgramAccount {
+    pub fn from_ts_expr(program: &ProgramModule, interface: &TsInterfaceDecl) -> Result<Self> {
+        if !extends(interface, "Account") {
+            Err(PoseidonError::AccountNotExtended.at(interface.id.span))?
+        }
+        let fields = interface_fields(program, interface)?;
+        Ok(Self {
+            name: interface.id.sym.to_string(),
+            // anchor discriminator
+            space: 8 + fields_space(program, &fields)?,
+            fields: fields.into_iter().map(|(field, _)| field).collect(),
+        })
+    }
+}
+
+impl ProgramEvent {
+    pub fn from_ts_interface(program: &ProgramModule, interface: &TsInterfaceDecl) -> Result<Self> {
+        Ok(Self {
+            name: interface.id.sym.to_string(),
+            fields: interface_fields(program, interface)?
+                .into_iter()
+                .map(|(field, _)| field)
+                .collect(),
+        })
+    }
+}
+
+impl ProgramConstant {
+    /// Reads `const NAME: T = literal`, where the type may be left out of strings,
+    /// booleans and `new Pubkey("...")`.
+    pub fn from_var_declarator(d: &VarDeclarator) -> Result<Self> {
+        let BindingIdent { id, type_ann } = d.name.as_ident().ok_or(
+            Diagnostic::new(d.name.span(), "destructuring is not supported")
+                .with_hint("declare one constant at a time"),
+        )?;
+        let init = d.init.as_deref().ok_or(
+            Diagnostic::new(d.span, format!("`{}` needs a value", id.sym))
+                .with_hint("constants are initialized where they are declared"),
+        )?;
+        let annotated = match type_ann {
+            Some(ann) => {
+                let of_type = extract_type(ann)?.0;
+                if !STANDARD_TYPES.contains(&of_type.as_str()) {
+                    Err(PoseidonError::InvalidType(of_type.clone()).at(ann.span))?
+                }
+                Some(of_type)
+            }
+            None => None,
+        };
+        let (value, inferred) = match init {
+            Expr::Lit(Lit::Str(s)) => (ConstantValue::Str(s.value.to_string()), Some("Str")),
+            Expr::Lit(Lit::Bool(b)) => (ConstantValue::Bool(b.value), Some("Boolean")),
+            Expr::New(n) if n.callee.as_ident().is_some_and(|i| i.sym == "Pubkey") => {
+                let address = match n.args.as_deref() {
+                    Some([arg]) => match &*arg.expr {
+                        Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+                .filter(|address| Pubkey::from_str(address).is_ok())
+                .ok_or(
+                    Diagnostic::new(n.span, "expected a base58 public key")
+                        .with_hint(r#"e.g. `new Pubkey("11111111111111111111111111111111")`"#),
+                )?;
+                (ConstantValue::Pubkey(address), Some("Pubkey"))
+            }
+            _ => match constant_int(init) {
+                Some(i) => (ConstantValue::Int(i), None),
+                None => Err(
+                    Diagnostic::new(init.span(), "constants must be literals").with_hint(
+                        r#"use a number, string or boolean literal, or `new Pubkey("...")`"#,
+                    ),
+                )?,
+            },
+        };
+        let of_type = annotated.or(inferred.map(String::from)).ok_or(
+            Diagnostic::new(id.span, format!("cannot infer the type of `{}`", id.sym))
+                .with_hint(format!("annotate it, e.g. `const {}: u64 = ...`", id.sym)),
+        )?;
+        let fits = match value {
+            ConstantValue::Int(_) => is_integer(&of_type),
+            _ => inferred == Some(of_type.as_str()),
+        };
+        if !fits {
+            Err(Diagnostic::new(
+                init.span(),
+                format!("expected a value of type `{}`", of_type),
+            ))?
+        }
+        Ok(ProgramConstant {
+            name: id.sym.as_ref().to_case(Case::UpperSnake),
+            of_type,
+            value,
+        })
+    }
+}
+
+/// The value of an integer literal, which may be negated.
+fn constant_int(e: &Expr) -> Option<i128> {
+    match e {
+        Expr::Paren(p) => constant_int(&p.expr),
+        Expr::Unary(u) if u.op == UnaryOp::Minus => constant_int(&u.arg).map(|i| -i),
+        Expr::Lit(lit) => match lower_lit(lit).ok()? {
+            Expression::Int(i) => Some(i),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether an exported `enum` declares errors rather than a type, which is told by its
+/// values being error messages. Types can't have values, so its name plays no part.
+fn is_error_enum(e: &TsEnumDecl) -> bool {
+    e.members
+        .iter()
+        .any(|m| matches!(m.init.as_deref(), Some(Expr::Lit(Lit::Str(_)))))
+}
+
+impl ProgramEnum {
+    pub fn from_ts_enum(e: &TsEnumDecl) -> Result<Self> {
+        let mut variants: Vec<String> = vec![];
+        for member in e.members.iter() {
+            if let Some(init) = member.init.as_deref() {
+                Err(
+                    Diagnostic::new(init.span(), "enum variants can't have values")
+                        .with_hint("variants are numbered in order, only errors take a message"),
+                )?
+            }
+            let name = match &member.id {
+                TsEnumMemberId::Ident(i) => i.sym.to_string(),
+                TsEnumMemberId::Str(s) => s.value.to_string(),
+            };
+            variants.push(name.to_case(Case::Pascal));
+        }
+        Ok(Self {
+            name: e.id.sym.to_string(),
+            variants,
+        })
+    }
+}
+
+impl ProgramError {
+    /// Reads `export enum MyError { Name = "message", ... }`.
+    pub fn from_ts_enum(e: &TsEnumDecl) -> Result<Vec<Self>> {
+        let mut errors: Vec<ProgramError> = vec![];
+        for member in e.members.iter() {
+            let name = match &member.id {
+                TsEnumMemberId::Ident(i) => i.sym.to_string(),
+                TsEnumMemberId::Str(s) => s.value.to_string(),
+            };
+            let msg = match member.init.as_deref() {
+                None => None,
+                Some(Expr::Lit(Lit::Str(s))) => Some(s.value.to_string()),
+                Some(other) => Err(Diagnostic::new(
+                    other.span(),
+                    "error messages must be string literals",
+                )
+                .with_hint(r#"write the variant as `Name = "message"`"#))?,
+            };
+            errors.push(ProgramError {
+                name: name.to_case(Case::Pascal),
+                msg,
+            });
+        }
+        Ok(errors)
+    }
+
+    /// Reads `export class MyError extends ProgramError { message = "..." }`, where the
+    /// message may also be passed to `super` in the constructor.
+    pub fn from_class_decl(c: &ClassDecl) -> Result<Self> {
+        match c.class.super_class.as_deref() {
+            Some(Expr::Ident(i)) if i.sym == "ProgramError" => {}
+            _ => Err(Diagnostic::new(c.ident.span, "unsupported exported class")
+                .with_hint("custom errors are declared as `class MyError extends ProgramError`"))?,
+        }
+        let mut msg: Option<String> = None;
+        for member in c.class.body.iter() {
+            match member {
+                ClassMember::ClassProp(p)
+                    if p.key.as_ident().is_some_and(|i| i.sym == "message") =>
+                {
+                    msg = Some(string_lit(p.value.as_deref(), p.span)?);
+                }
+                ClassMember::Constructor(ctor) => {
+                    let super_call = ctor
+                        .body
+                        .iter()
+                        .flat_map(|b| b.stmts.iter())
+                        .filter_map(|s| s.as_expr()?.expr.as_call())
+                        .find(|call| matches!(call.callee, Callee::Super(_)));
+                    if let Some(call) = super_call {
+                        msg = Some(string_lit(call.args.first().map(|a| &*a.expr), call.span)?);
+                    }
+                }
+                _ => Err(
+                    Diagnostic::new(member.span(), "unsupported error class member")
+                        .with_hint(r#"only a `message = "..."` property is supported"#),
+                )?,
+            }
+        }
+        Ok(ProgramError {
+            name: c.ident.sym.to_string().to_case(Case::Pascal),
+            msg,
+        })
+    }
+}
+
+fn string_lit(e: Option<&Expr>, span: Span) -> Result<String> {
+    match e {
+        Some(Expr::Lit(Lit::Str(s))) => Ok(s.value.to_string()),
+        Some(other) => Err(Diagnostic::new(other.span(), "expected a string literal").into()),
+        None => Err(Diagnostic::new(span, "expected a string literal").into()),
+    }
+}
+
+/// Reads a type annotation into the canonical name of its type, like `Vec<Array<u8, 32>>`,
+/// and the maximum lengths of the `Str`s and `Vec`s in it, outermost first. The lengths
+/// are left out unless all of them are given.
+fn extract_type(binding: &TsTypeAnn) -> Result<(String, Vec<u32>), Error> {
+    let mut max_len = Some(vec![]);
+    let of_type = ts_type(&binding.type_ann, &mut max_len)?;
+    Ok((of_type, max_len.unwrap_or_default()))
+}
+
+fn ts_type(ts_type_ann: &TsType, max_len: &mut Option<Vec<u32>>) -> Result<String, Error> {
+    match ts_type_ann {
+        TsType::TsTypeRef(type_ref) => {
+            let name = type_ref
+                .type_name
+                .as_ident()
+                .ok_or(PoseidonError::IdentNotFound.at(type_ref.type_name.span()))?
+                .sym
+                .as_ref();
+            let params = match &type_ref.type_params {
+                Some(type_params) => type_params.params.as_slice(),
+                None => &[],
+            };
+            let mut push_len = |param: &TsType| -> Result<()> {
+                let len = type_param_len(param)?;
+                if let Some(lens) = max_len.as_mut() {
+                    lens.push(len);
+                }
+                Ok(())
+            };
+            match (name, params) {
+                ("Str", []) => {
+                    *max_len = None;
+                    Ok(String::from("Str"))
+                }
+                ("Str", [len]) => {
+                    push_len(len)?;
+                    Ok(String::from("Str"))
+                }
+                ("Vec", [item]) => {
+                    *max_len = None;
+                    Ok(format!("Vec<{}>", ts_type(item, max_len)?))
+                }
+                ("Vec", [item, len]) => {
+                    push_len(len)?;
+                    Ok(format!("Vec<{}>", ts_type(item, max_len)?))
+                }
+                ("Option", [item]) => Ok(format!("Option<{}>", ts_type(item, max_len)?)),
+                // Arrays have a fixed length, which isn't one of the maximum lengths
+                ("Array", [item, len]) => Ok(format!(
+                    "Array<{}, {}>",
+                    ts_type(item, max_len)?,
+                    type_param_len(len)?
+                )),
+                (name, []) => Ok(name.to_string()),
+                (name, _) => Err(
+                    PoseidonError::KeyWordTypeNotSupported(format!("{}<..>", name))
+                        .at(type_ref.span)
+                        .into(),
+                ),
+            }
+        }
+        TsType::TsKeywordType(keyword) => {
+            let name = match keyword.kind {
+                TsKeywordTypeKind::TsNumberKeyword => "number",
+                TsKeywordTypeKind::TsStringKeyword => "string",
+                TsKeywordTypeKind::TsBooleanKeyword => "boolean",
+                TsKeywordTypeKind::TsBigIntKeyword => "bigint",
+                TsKeywordTypeKind::TsAnyKeyword => "any",
+                _ => "keyword",
+            };
+            Err(PoseidonError::KeyWordTypeNotSupported(name.to_string())
+                .at(keyword.span)
+                .into())
+        }
+        other => Err(PoseidonError::InvalidType(String::from("annotation"))
+            .at(other.span())
+            .into()),
+    }
+}
+
+/// Reads the numeric literal of a type parameter like the `32` in `Str<32>`.
+fn type_param_len(param: &TsType) -> Result<u32, Error> {
+    Ok(param
+        .as_ts_lit_type()
+        .ok_or(PoseidonError::TSLiteralTypeNotFound.at(param.span()))?
+        .lit
+        .as_number()
+        .ok_or(PoseidonError::NumericLiteralNotFound.at(param.span()))?
+        .value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ir::{AccountType, ArgPassing, CpiProgram, Expression, Statement},
+        transpiler::tests::{lower_source, program, transpile_source},
+    };
+
+    const COUNTER: &str = "export interface Counter extends Account { count: u64; bump: u8 }";
+
+    #[test]
+    fn instruction_keeps_parameter_order() {
+        let source = program(
+            "initialize(counter: Counter, start: u64, payer: Signer, step: u8): Result {
+                counter.derive([\"counter\", payer.key]).init(payer);
+                counter.count = start;
+            }",
+            COUNTER,
+        );
+        let program = lower_source(&source).unwrap();
+        let ix = &program.instructions[0];
+        let accounts: Vec<&str> = ix.accounts.iter().map(|a| a.name.as_str()).collect();
+        let args: Vec<&str> = ix.args.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(accounts, ["counter", "payer"]);
+        assert_eq!(args, ["start", "step"]);
+        assert_eq!(
+            ix.accounts[0].of_type,
+            AccountType::Custom("Counter".to_string())
+        );
+        let init = ix.accounts[0].constraints.init.as_ref().unwrap();
+        assert_eq!(init.payer, "payer");
+        assert!(ix.uses_system_program);
+    }
+
+    #[test]
+    fn accounts_struct_follows_parameter_order() {
+        let rust = transpile_source(&program(
+            "initialize(counter: Counter, payer: Signer): Result {
+                counter.derive([\"counter\", payer.key]).init(payer);
+            }",
+            COUNTER,
+        ));
+        let counter = rust.find("pub counter: Account<'info, Counter>").unwrap();
+        let payer = rust.find("pub payer: Signer<'info>").unwrap();
+        let system_program = rust.find("pub system_program").unwrap();
+        assert!(counter < payer && payer < system_program, "{}", rust);
+    }
+
+    #[test]
+    fn only_annotated_arrays_are_seeds() {
+        let withdraw = |seeds: &str| {
+            lower_source(&program(
+                &format!(
+                    "withdraw(vault: Signer, owner: Signer, amount: u64, bump: u8): Result {{
+                        {}
+                        SystemProgram.transfer(vault, owner, amount, seeds);
+                    }}",
+                    seeds
+                ),
+                "",
+            ))
+        };
+        let program =
+            withdraw("const seeds: Seeds = [\"vault\", owner.key, bump.toBytes()];").unwrap();
+        let Statement::Cpi(cpi) = &program.instructions[0].body[0] else {
+            panic!("expected a CPI");
+        };
+        assert_eq!(cpi.signer_seeds.as_ref().map(Vec::len), Some(3));
+        assert_eq!(
+            withdraw("const seeds = [\"vault\", owner.key, bump.toBytes()];").unwrap_err(),
+            ["arrays can only be declared as `Seeds`"]
+        );
+    }
+
+    #[test]
+    fn emit_reads_event_fields_like_other_objects() {
+        let emit = |event: &str| {
+            lower_source(&program(
+                &format!(
+                    "deposit(user: Signer, amount: u64): Result {{ {}; }}",
+                    event
+                ),
+                "export interface Deposited extends Event { user: Pubkey; amount: u64 }",
+            ))
+        };
+        for event in [
+            "emit(new Deposited({ user: user.key, amount }))",
+            "emit<Deposited>({ \"user\": user.key, amount: amount })",
+        ] {
+            let program = emit(event).unwrap_or_else(|e| panic!("{}: {:?}", event, e));
+            let Statement::Emit { fields, .. } = &program.instructions[0].body[0] else {
+                panic!("expected an emit");
+            };
+            let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, ["user", "amount"]);
+        }
+        assert_eq!(
+            emit("emit(new Deposited({ [\"user\"]: user.key, amount }))").unwrap_err(),
+            ["invalid field in event `Deposited`"]
+        );
+        assert_eq!(
+            lower_source(&program(
+                "create(payer: Signer, mint: Mint): Result { mint.has({ ...payer }); }",
+                "",
+            ))
+            .unwrap_err(),
+            ["invalid field in the mint options"]
+        );
+    }
+
+    #[test]
+    fn enums_are_errors_when_their_values_are_messages() {
+        let lowered = lower_source(&program(
+            "check(owner: Signer, status: StatusError): Result {
+                if (status === StatusError.Closed) {
+                    throw VaultError.Locked;
+                }
+            }",
+            "export enum StatusError { Open, Closed }
+            export enum VaultError { ZeroAmount = \"Amount must be positive\", Locked }
+            export enum Faults { Broken = \"It broke\" }",
+        ))
+        .unwrap();
+        let enums: Vec<&str> = lowered.enums.iter().map(|e| e.name.as_str()).collect();
+        let errors: Vec<&str> = lowered.errors.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(enums, ["StatusError"]);
+        assert_eq!(errors, ["ZeroAmount", "Locked", "Broken"]);
+
+        let unknown = lower_source(&program(
+            "check(owner: Signer): Result { throw Codes.Locked; }",
+            "export enum Codes { Locked }",
+        ));
+        assert_eq!(unknown.unwrap_err(), ["cannot find error `Locked`"]);
+    }
+
+    const TYPES: &str = "export enum Side { Bid, Ask }
+        export interface Point { x: u64; y: u64 }
+        export interface Shape { name: Str<8>; points: Vec<Point, 3>; side: Side }";
+
+    #[test]
+    fn space_of_types() {
+        let lowered = lower_source(&program("", TYPES)).unwrap();
+        let space = |of_type: &str, max_len: &[u32]| lowered.space(of_type, max_len).unwrap();
+        assert_eq!(space("u8", &[]), 1);
+        assert_eq!(space("Pubkey", &[]), 32);
+        assert_eq!(space("Str", &[10]), 4 + 10);
+        assert_eq!(space("Vec<u64>", &[5]), 4 + 5 * 8);
+        assert_eq!(space("Vec<Str>", &[5, 10]), 4 + 5 * (4 + 10));
+        assert_eq!(space("Vec<Vec<u16>>", &[2, 3]), 4 + 2 * (4 + 3 * 2));
+        assert_eq!(space("Array<u32, 4>", &[]), 4 * 4);
+        assert_eq!(space("Array<Str, 2>", &[6]), 2 * (4 + 6));
+        assert_eq!(space("Vec<Array<u8, 32>>", &[3]), 4 + 3 * 32);
+        assert_eq!(space("Option<u64>", &[]), 1 + 8);
+        assert_eq!(space("Option<Str>", &[6]), 1 + 4 + 6);
+        assert_eq!(space("Side", &[]), 1);
+        assert_eq!(space("Point", &[]), 16);
+        assert_eq!(space("Shape", &[]), (4 + 8) + (4 + 3 * 16) + 1);
+        assert_eq!(space("Vec<Shape>", &[2]), 4 + 2 * 65);
+
+        let message =
+            |of_type: &str, max_len: &[u32]| lowered.space(of_type, max_len).unwrap_err().message;
+        assert_eq!(message("Str", &[]), "`Str` needs a maximum length");
+        assert_eq!(message("Vec<Str>", &[5]), "`Str` needs a maximum length");
+        assert_eq!(
+            message("Uint8Array", &[]),
+            "`Uint8Array` can't be stored without a maximum length"
+        );
+    }
+
+    #[test]
+    fn account_space_includes_discriminator() {
+        let lowered = lower_source(&program(
+            "",
+            &format!(
+                "{}\nexport interface Drawing extends Account {{ shape: Shape; owner: Pubkey }}",
+                TYPES
+            ),
+        ))
+        .unwrap();
+        assert_eq!(lowered.account("Drawing").unwrap().space, 8 + 65 + 32);
+    }
+
+    #[test]
+    fn recursive_structs_are_reported() {
+        let lowered = lower_source(&program(
+            "",
+            "export interface Node { value: u64; children: Vec<Tree, 2> }
+            export interface Tree { root: Node }",
+        ));
+        assert_eq!(
+            lowered.unwrap_err(),
+            ["`Tree` contains itself", "`Node` contains itself"]
+        );
+        let lowered = lower_source(&program(
+            "",
+            "export interface List { value: u64; next: Option<List> }",
+        ));
+        assert_eq!(lowered.unwrap_err(), ["`List` contains itself"]);
+    }
+
+    #[test]
+    fn uint8array_fields_are_rejected() {
+        let stored = lower_source(&program(
+            "",
+            "export interface Blob extends Account { data: Uint8Array }",
+        ));
+        assert_eq!(
+            stored.unwrap_err(),
+            ["`Uint8Array` can't be stored without a maximum length"]
+        );
+    }
+
+    #[test]
+    fn integer_cpi_arguments_are_type_checked() {
+        let transfer = |amount: &str| {
+            lower_source(&program(
+                &format!(
+                    "pay(from: Signer, to: Signer, amount: u64, flag: Boolean): Result {{
+                        SystemProgram.transfer(from, to, {});
+                    }}",
+                    amount
+                ),
+                "",
+            ))
+        };
+        assert!(transfer("amount").is_ok());
+        assert!(transfer("1000").is_ok());
+        assert!(transfer("amount * 2").is_ok());
+        assert_eq!(
+            transfer("to.key").unwrap_err(),
+            ["expected an integer, found `Pubkey`"]
+        );
+        assert_eq!(
+            transfer("flag").unwrap_err(),
+            ["expected an integer, found `Boolean`"]
+        );
+
+        let create = lower_source(&program(
+            "create(payer: Signer, account: Signer, owner: Pubkey): Result {
+                SystemProgram.createAccount(payer, account, 1000000, owner, owner);
+            }",
+            "",
+        ));
+        assert_eq!(create.unwrap_err(), ["expected an integer, found `Pubkey`"]);
+    }
+
+    #[test]
+    fn integer_literals_keep_every_digit() {
+        let assign = |value: &str| {
+            lower_source(&program(
+                &format!(
+                    "set(owner: Signer, counter: Counter): Result {{
+                        counter.derive([\"counter\", owner.key]);
+                        counter.count = {};
+                    }}",
+                    value
+                ),
+                COUNTER,
+            ))
+        };
+        let value = |value: &str| {
+            let program = assign(value).unwrap();
+            let Statement::Assign { value, .. } = &program.instructions[0].body[0] else {
+                panic!("expected an assignment");
+            };
+            format!("{:?}", value)
+        };
+        assert!(value("9007199254740993").contains("Int(9007199254740993)"));
+        assert!(value("0xffff_ffff_ffff_ffff").contains("Int(18446744073709551615)"));
+        assert!(value("1e9").contains("Int(1000000000)"));
+        assert_eq!(
+            assign("170141183460469231731687303715884105728").unwrap_err(),
+            ["integer literal is too large"]
+        );
+    }
+
+    const ORDER: &str = "export enum Side { Bid, Ask, Cancel }
+        export interface Order extends Account { side: Side; filled: u64 }";
+
+    /// Lowers `cases` as the body of a `switch` over the side of an order.
+    fn lower_switch(cases: &str) -> Result<Statement, Vec<String>> {
+        let lowered = lower_source(&program(
+            &format!(
+                "fill(owner: Signer, order: Order, amount: u64): Result {{
+                    order.derive([\"order\", owner.key]);
+                    switch (order.side) {{ {} }}
+                }}",
+                cases
+            ),
+            ORDER,
+        ))?;
+        Ok(lowered.instructions[0].body[0].clone())
+    }
+
+    #[test]
+    fn switches_over_program_enums() {
+        let exhaustive = lower_switch(
+            "case Side.Bid:
+                order.filled = amount;
+                break;
+            case Side.Ask:
+            case Side.Cancel: {
+                order.filled = 0;
+                break;
+            }",
+        )
+        .unwrap();
+        let Statement::Match {
+            arms, otherwise, ..
+        } = exhaustive
+        else {
+            panic!("expected a match");
+        };
+        let sizes: Vec<(usize, usize)> = arms.iter().map(|(v, b)| (v.len(), b.len())).collect();
+        // Shared bodies keep every value and lose their `break`
+        assert_eq!(sizes, [(1, 1), (2, 1)]);
+        assert!(otherwise.is_none());
+
+        let partial = lower_switch("case Side.Bid: order.filled = amount; break;").unwrap();
+        let Statement::Match { otherwise, .. } = partial else {
+            panic!("expected a match");
+        };
+        assert_eq!(otherwise.map(|o| o.len()), Some(0));
+
+        let with_default = lower_switch(
+            "case Side.Bid: order.filled = amount; break;
+            default: return;",
+        )
+        .unwrap();
+        let Statement::Match { otherwise, .. } = with_default else {
+            panic!("expected a match");
+        };
+        assert!(matches!(otherwise.as_deref(), Some([Statement::Return])));
+    }
+
+    #[test]
+    fn switch_cases_cannot_fall_through() {
+        let fallthrough = lower_switch(
+            "case Side.Bid:
+                order.filled = amount;
+            case Side.Ask:
+                order.filled = 0;
+                break;",
+        );
+        assert_eq!(fallthrough.unwrap_err(), ["cases can't fall through"]);
+    }
+
+    const VAULT: &str =
+        "export enum VaultError { ZeroAmount = \"Amount must be greater than zero\", Locked }
+        export interface Vault extends Account { owner: Pubkey; balance: u64 }";
+
+    #[test]
+    fn control_flow_lowers_to_statements() {
+        let source = program(
+            "withdraw(owner: Signer, state: Vault, amount: u64): Result {
+                state.derive([\"vault\", owner.key]).has([owner]);
+                require(amount > 0, VaultError.ZeroAmount);
+                if (amount > state.balance) {
+                    throw VaultError.Locked;
+                } else if (amount === state.balance) {
+                    state.balance = 0;
+                } else {
+                    state.balance -= amount;
+                }
+            }",
+            VAULT,
+        );
+        let lowered = lower_source(&source).unwrap();
+        let body = &lowered.instructions[0].body;
+        assert!(matches!(&body[0], Statement::Require { error, .. } if error == "ZeroAmount"));
+        let Statement::If {
+            then, otherwise, ..
+        } = &body[1]
+        else {
+            panic!("expected an if");
+        };
+        assert!(matches!(then.as_slice(), [Statement::Throw(error)] if error == "Locked"));
+        // `else if` is an `if` alone in the else branch
+        assert!(matches!(
+            otherwise.as_deref(),
+            Some([Statement::If {
+                otherwise: Some(_),
+                ..
+            }])
+        ));
+
+        let rust = transpile_source(&source);
+        assert!(
+            rust.contains("require!(amount > 0, ErrorCode::ZeroAmount);"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("return err!(ErrorCode::Locked);"));
+        assert!(rust.contains("} else if amount == ctx.accounts.state.balance {"));
+    }
+
+    #[test]
+    fn control_flow_errors_are_reported() {
+        let errors = lower_source(&program(
+            "withdraw(owner: Signer, state: Vault, amount: u64): Result {
+                if (amount > 0) {
+                    state.derive([\"vault\", owner.key]);
+                }
+                require(amount > 0, VaultError.Missing);
+                throw new Missing();
+            }",
+            VAULT,
+        ))
+        .unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                "account constraints cannot be set conditionally",
+                "cannot find error `Missing`",
+                "cannot find error `Missing`",
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_can_be_classes() {
+        let source = program(
+            "withdraw(owner: Signer, state: Vault, amount: u64): Result {
+                state.derive([\"vault\", owner.key]);
+                require(amount > 0, ZeroAmount);
+                if (amount > state.balance) {
+                    throw new InsufficientFunds();
+                }
+                throw Frozen;
+            }",
+            "export interface Vault extends Account { balance: u64 }
+            export class ZeroAmount extends ProgramError { message = \"Amount must be positive\" }
+            export class InsufficientFunds extends ProgramError {
+                constructor() { super(\"Not enough lamports\"); }
+            }
+            export class Frozen extends ProgramError {}",
+        );
+        let lowered = lower_source(&source).unwrap();
+        let errors: Vec<(&str, Option<&str>)> = lowered
+            .errors
+            .iter()
+            .map(|e| (e.name.as_str(), e.msg.as_deref()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                ("ZeroAmount", Some("Amount must be positive")),
+                ("InsufficientFunds", Some("Not enough lamports")),
+                ("Frozen", None),
+            ]
+        );
+        let rust = transpile_source(&source);
+        assert!(
+            rust.contains("require!(amount > 0, ErrorCode::ZeroAmount);"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("return err!(ErrorCode::InsufficientFunds);"));
+        assert!(rust.contains("return err!(ErrorCode::Frozen);"));
+    }
+
+    #[test]
+    fn error_classes_are_checked() {
+        let errors = lower_source(&program(
+            "",
+            "export class Helper {}
+            export class Detailed extends ProgramError { code = 1 }
+            export class Twice extends ProgramError {}
+            export enum Errors { Twice = \"declared again\" }",
+        ))
+        .unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                "unsupported exported class",
+                "unsupported error class member",
+                "error `Twice` is declared more than once",
+            ]
+        );
+    }
+
+    const LIST: &str =
+        "export interface TodoList extends Account { owner: Pubkey; items: Vec<Str<60>, 10> }";
+
+    #[test]
+    fn accounts_can_be_reallocated() {
+        let source = program(
+            "grow(owner: Signer, list: TodoList, count: u32): Result {
+                list.derive([\"list\", owner.key]).has([owner]).realloc(owner, 8 + 32 + 4 + count * 64, false);
+            }",
+            LIST,
+        );
+        let lowered = lower_source(&source).unwrap();
+        let ix = &lowered.instructions[0];
+        let list = ix.account("list").unwrap();
+        let realloc = list.constraints.realloc.as_ref().unwrap();
+        assert_eq!(realloc.payer, "owner");
+        assert!(!realloc.zero);
+        // The space is a `usize`, whatever the type of the arguments in it
+        assert!(matches!(&realloc.space, Expression::Cast { to, .. } if to == "usize"));
+        assert!(list.is_writable());
+        assert!(ix.uses_system_program);
+
+        let rust = transpile_source(&source);
+        // The space reads an argument, which Anchor has to deserialize first
+        assert!(rust.contains("#[instruction(count"), "{}", rust);
+        assert!(rust.contains("realloc::payer = owner"));
+        assert!(rust.contains("realloc::zero = false"));
+    }
+
+    #[test]
+    fn realloc_arguments_are_checked() {
+        let realloc = |account: &str, args: &str| {
+            lower_source(&program(
+                &format!(
+                    "grow(owner: Signer, list: TodoList, count: u32, flag: Boolean): Result {{
+                        list.derive([\"list\", owner.key]);
+                        const size: u32 = 100;
+                        {}.realloc({});
+                    }}",
+                    account, args
+                ),
+                LIST,
+            ))
+            .unwrap_err()
+        };
+        assert_eq!(
+            realloc("owner", "owner, 100, false"),
+            ["only custom accounts can be reallocated"]
+        );
+        assert_eq!(
+            realloc("list", "owner, size, false"),
+            ["account constraints cannot use locals"]
+        );
+        assert_eq!(
+            realloc("list", "owner, flag, false"),
+            ["expected an integer space, found `Boolean`"]
+        );
+        assert_eq!(
+            realloc("list", "owner, 100, flag"),
+            ["expected `true` or `false`"]
+        );
+    }
+
+    const PAYMENT: &str =
+        "export const FEE_RECEIVER = new Pubkey(\"GjJyeC1r2RgkuoCWMyPYkCWSGSGLcz266EaAkLA27AhL\");
+        export const ORACLE_PROGRAM = new Pubkey(\"FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH\");
+        export interface Config extends Account { program: Pubkey; fee: u64 }";
+
+    #[test]
+    fn accounts_can_be_pinned_down() {
+        let source = program(
+            "pay(feeReceiver: SystemAccount, oracle: UncheckedAccount, program: UncheckedAccount, config: Config, admin: Pubkey): Result {
+                feeReceiver.address(FEE_RECEIVER);
+                oracle.owner(ORACLE_PROGRAM).address(admin);
+                program.executable().address(config.program);
+            }",
+            PAYMENT,
+        );
+        let lowered = lower_source(&source).unwrap();
+        let ix = &lowered.instructions[0];
+        let constraints = |name: &str| &ix.account(name).unwrap().constraints;
+        assert!(matches!(
+            &constraints("fee_receiver").address,
+            Some(Expression::Const(name)) if name == "FEE_RECEIVER"
+        ));
+        assert!(matches!(
+            &constraints("oracle").owner,
+            Some(Expression::Const(name)) if name == "ORACLE_PROGRAM"
+        ));
+        assert!(matches!(
+            &constraints("oracle").address,
+            Some(Expression::Arg(name)) if name == "admin"
+        ));
+        assert!(constraints("program").executable);
+
+        let rust = transpile_source(&source);
+        assert!(
+            rust.contains("#[account(mut, address = FEE_RECEIVER)]"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("#[account(address = admin, owner = ORACLE_PROGRAM)]"));
+        assert!(rust.contains("#[account(address = config.program, executable)]"));
+    }
+
+    #[test]
+    fn pinned_keys_are_pubkeys() {
+        let pin = |args: &str| {
+            lower_source(&program(
+                &format!(
+                    "pay(oracle: UncheckedAccount, config: Config): Result {{
+                        const key: Pubkey = config.program;
+                        oracle.{};
+                    }}",
+                    args
+                ),
+                PAYMENT,
+            ))
+            .unwrap_err()
+        };
+        assert_eq!(
+            pin("owner(config.fee)"),
+            ["expected a `Pubkey`, found `u64`"]
+        );
+        assert_eq!(pin("address(1)"), ["expected a `Pubkey`, found `integer`"]);
+        assert_eq!(
+            pin("address(key)"),
+            ["account constraints cannot use locals"]
+        );
+    }
+
+    #[test]
+    fn mints_can_be_initialized_and_checked() {
+        let source = program(
+            "createMint(payer: Signer, auth: UncheckedAccount, mint: Mint, other: Mint): Result {
+                auth.derive([\"auth\"]);
+                mint.derive([\"mint\"]).init(payer, { decimals: 6, authority: auth, freezeAuthority: payer.key });
+                other.has({ decimals: 9, authority: auth });
+            }",
+            "",
+        );
+        let lowered = lower_source(&source).unwrap();
+        let ix = &lowered.instructions[0];
+        assert!(ix.uses_token_program);
+        let mint = ix
+            .account("mint")
+            .unwrap()
+            .constraints
+            .mint
+            .as_ref()
+            .unwrap();
+        assert!(matches!(mint.decimals, Some(Expression::Int(6))));
+        assert_eq!(mint.authority.as_deref(), Some("auth"));
+        assert_eq!(mint.freeze_authority.as_deref(), Some("payer"));
+        let other = ix
+            .account("other")
+            .unwrap()
+            .constraints
+            .mint
+            .as_ref()
+            .unwrap();
+        assert!(matches!(other.decimals, Some(Expression::Int(9))));
+        assert_eq!(other.freeze_authority, None);
+
+        let rust = transpile_source(&source);
+        for constraint in [
+            "mint::decimals = 6",
+            "mint::authority = auth",
+            "mint::freeze_authority = payer",
+            "mint::decimals = 9",
+            "pub token_program: Program<'info, Token>",
+        ] {
+            assert!(rust.contains(constraint), "{}", rust);
+        }
+    }
+
+    #[test]
+    fn mint_options_are_checked() {
+        let mint = |call: &str| {
+            lower_source(&program(
+                &format!(
+                    "createMint(payer: Signer, auth: UncheckedAccount, mint: Mint, amount: u64): Result {{
+                        mint.{};
+                    }}",
+                    call
+                ),
+                "",
+            ))
+            .unwrap_err()
+        };
+        assert_eq!(
+            mint("init(payer, { decimals: 6 })"),
+            ["new mints need `decimals` and an `authority`"]
+        );
+        assert_eq!(
+            mint("init(payer, { decimals: 6, authority: auth, supply: 1 })"),
+            ["unknown mint option `supply`"]
+        );
+        assert_eq!(
+            mint("has({ decimals: amount })"),
+            ["expected `u8` decimals, found `u64`"]
+        );
+        assert_eq!(
+            mint("has({ authority: owner })"),
+            ["`owner` is not an account of this instruction"]
+        );
+        assert_eq!(
+            mint("init(payer, 6)"),
+            ["mint options are given as an object literal"]
+        );
+    }
+
+    #[test]
+    fn associated_token_accounts_can_be_created_in_the_body() {
+        let source = program(
+            "send(payer: Signer, recipient: UncheckedAccount, recipientAta: UncheckedAccount, mint: Mint, fresh: Boolean): Result {
+                if (fresh) {
+                    AssociatedTokenProgram.create(payer, recipientAta, recipient, mint);
+                } else {
+                    AssociatedTokenProgram.createIdempotent(payer, recipientAta, recipient, mint);
+                }
+            }",
+            "",
+        );
+        let lowered = lower_source(&source).unwrap();
+        let ix = &lowered.instructions[0];
+        assert!(ix.uses_associated_token_program);
+        assert!(ix.uses_token_program);
+        assert!(ix.uses_system_program);
+        assert!(ix.account("recipient_ata").unwrap().constraints.is_mut);
+        let Statement::If {
+            then, otherwise, ..
+        } = &ix.body[0]
+        else {
+            panic!("expected an if statement");
+        };
+        let [Statement::Cpi(create)] = then.as_slice() else {
+            panic!("expected a CPI");
+        };
+        assert_eq!(create.program, CpiProgram::AssociatedToken);
+        assert_eq!(create.instruction, "create");
+        assert_eq!(
+            create.accounts,
+            [
+                ("payer", "payer"),
+                ("associated_token", "recipient_ata"),
+                ("authority", "recipient"),
+                ("mint", "mint"),
+                ("system_program", "system_program"),
+                ("token_program", "token_program"),
+            ]
+            .map(|(field, account)| (field.to_string(), account.to_string()))
+        );
+        assert!(matches!(
+            otherwise.as_deref(),
+            Some([Statement::Cpi(cpi)]) if cpi.instruction == "create_idempotent"
+        ));
+
+        let rust = transpile_source(&source);
+        for line in [
+            "create(cpi_ctx)?;",
+            "create_idempotent(cpi_ctx)?;",
+            "associated_token: ctx.accounts.recipient_ata.to_account_info(),",
+            "pub associated_token_program: Program<'info, AssociatedToken>,",
+            "pub token_program: Program<'info, Token>,",
+            "pub system_program: Program<'info, System>,",
+        ] {
+            assert!(rust.contains(line), "{}", rust);
+        }
+    }
+
+    #[test]
+    fn associated_token_calls_are_checked() {
+        let send = |call: &str| {
+            lower_source(&program(
+                &format!(
+                    "send(payer: Signer, recipient: UncheckedAccount, recipientAta: UncheckedAccount, mint: Mint): Result {{
+                        AssociatedTokenProgram.{};
+                    }}",
+                    call
+                ),
+                "",
+            ))
+            .unwrap_err()
+        };
+        assert_eq!(
+            send("createAccount(payer, recipientAta, recipient, mint)"),
+            ["`AssociatedTokenProgram.createAccount` is not supported"]
+        );
+        assert_eq!(
+            send("create(payer, recipientAta, owner, mint)"),
+            ["`owner` is not an account of this instruction"]
+        );
+        assert_eq!(
+            send("createIdempotent(payer, recipientAta, recipient)"),
+            ["missing argument 4 for `AssociatedTokenProgram.createIdempotent`"]
+        );
+    }
+
+    const SEEDED: &str =
+        "export const STAKE_PROGRAM = new Pubkey(\"Stake11111111111111111111111111111111111111\");
+        export const SEED: Str = \"stake\";";
+
+    #[test]
+    fn accounts_can_be_created_and_drained_with_seeds() {
+        let source = program(
+            "open(payer: Signer, stake: UncheckedAccount, base: UncheckedAccount, lamports: u64, space: u64): Result {
+                base.derive([\"base\"]);
+                SystemProgram.createAccountWithSeed(payer, stake, base, SEED, lamports, space, STAKE_PROGRAM, [\"base\", base.getBump()]);
+            }
+            sweep(from: UncheckedAccount, base: UncheckedAccount, to: SystemAccount, seed: Str, owner: Pubkey, lamports: u64): Result {
+                SystemProgram.transferWithSeed(from, base, to, seed, owner, lamports);
+            }",
+            SEEDED,
+        );
+        let lowered = lower_source(&source).unwrap();
+        let [open, sweep] = lowered.instructions.as_slice() else {
+            panic!("expected two instructions");
+        };
+        let Statement::Cpi(create) = &open.body[0] else {
+            panic!("expected a CPI");
+        };
+        assert_eq!(create.instruction, "create_account_with_seed");
+        assert!(create.signer_seeds.is_some());
+        assert!(matches!(
+            create.args.as_slice(),
+            [(Expression::Const(seed), ArgPassing::Ref), _, _, (Expression::Const(owner), ArgPassing::Ref)]
+                if seed == "SEED" && owner == "STAKE_PROGRAM"
+        ));
+        // `base` only signs, while the lamports of the others change
+        assert!(open.account("stake").unwrap().constraints.is_mut);
+        assert!(!open.account("base").unwrap().constraints.is_mut);
+        assert!(!sweep.account("base").unwrap().constraints.is_mut);
+        assert!(sweep.account("from").unwrap().constraints.is_mut);
+        assert!(sweep.account("to").unwrap().constraints.is_mut);
+
+        let rust = transpile_source(&source);
+        assert!(rust.contains(
+            "create_account_with_seed(cpi_ctx, &SEED, lamports, space, &STAKE_PROGRAM)?;"
+        ));
+        assert!(rust.contains("transfer_with_seed(cpi_ctx, seed.to_string(), &owner, lamports)?;"));
+        assert!(rust.contains("base: ctx.accounts.base.to_account_info(),"));
+    }
+
+    #[test]
+    fn seeded_calls_take_a_seed_and_an_owner() {
+        let sweep = |args: &str| {
+            lower_source(&program(
+                &format!(
+                    "sweep(from: UncheckedAccount, base: Signer, to: SystemAccount, seed: Str, owner: Pubkey, lamports: u64): Result {{
+                        SystemProgram.transferWithSeed(from, base, to, {});
+                    }}",
+                    args
+                ),
+                SEEDED,
+            ))
+            .unwrap_err()
+        };
+        assert_eq!(
+            sweep("lamports, owner, lamports"),
+            ["expected a `Str`, found `u64`"]
+        );
+        assert_eq!(
+            sweep("seed, SEED, lamports"),
+            ["expected a `Pubkey`, found `Str`"]
+        );
+        assert_eq!(
+            sweep("seed, owner, owner"),
+            ["expected an integer, found `Pubkey`"]
+        );
+    }
+}
@@ -1,17 +1,20 @@
 mod cli;
+mod codegen;
+mod diagnostics;
 mod errors;
+mod ir;
+mod lower;
 mod parse_ts;
-mod rs_types;
 mod transpiler;
 mod ts_types;
 
 use anyhow::Result;
 use clap::{Parser as ClapParser, Subcommand};
 use parse_ts::parse_ts;
-use swc_ecma_ast::Module;
+use std::path::Path;
 
 use cli::{build_workspace, init, run_tests, sync_program_ids};
-use transpiler::transpile;
+use transpiler::{generate_client, generate_idl, transpile, transpile_crate};
 
 #[derive(ClapParser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -34,9 +37,36 @@ enum Commands {
         /// Input Typescript file path
         #[arg(short, long, help = "Input Typescript file")]
         input: String,
-        /// Output Rust file path
+        /// Output Rust file path, or the `src` directory with `--split`
         #[arg(short, long, help = "Output Rust file")]
         output: String,
+        /// Also write the Anchor IDL to this path
+        #[arg(long, help = "Output IDL JSON file")]
+        idl: Option<String>,
+        /// Write the standard Anchor crate layout instead of a single file
+        #[arg(
+            long,
+            help = "Write lib.rs, instructions/, state/... into the output directory"
+        )]
+        split: bool,
+    },
+    /// Generate the Anchor IDL of a Typescript program
+    Idl {
+        /// Input Typescript file path
+        #[arg(short, long, help = "Input Typescript file")]
+        input: String,
+        /// Output IDL file path
+        #[arg(short, long, help = "Output IDL JSON file")]
+        output: String,
+    },
+    /// Generate a typed TypeScript client of a Typescript program
+    Client {
+        /// Input Typescript file path
+        #[arg(short, long, help = "Input Typescript file")]
+        input: String,
+        /// Output client file path
+        #[arg(short, long, help = "Output TypeScript client file")]
+        output: String,
     },
     /// Initializes a new workspace
     Init {
@@ -58,9 +88,27 @@ fn main() -> Result<()> {
         Commands::Build => {
             build_workspace()?;
         }
-        Commands::Compile { input, output } => {
-            let module: Module = parse_ts(input);
-            transpile(&module, output)?;
+        Commands::Compile {
+            input,
+            output,
+            idl,
+            split,
+        } => {
+            let (module, source_map) = parse_ts(input)?;
+            match split {
+                true => {
+                    transpile_crate(&module, source_map, Path::new(output), idl.as_deref())?;
+                }
+                false => transpile(&module, source_map, output, idl.as_deref())?,
+            }
+        }
+        Commands::Idl { input, output } => {
+            let (module, source_map) = parse_ts(input)?;
+            generate_idl(&module, source_map, output)?;
+        }
+        Commands::Client { input, output } => {
+            let (module, source_map) = parse_ts(input)?;
+            generate_client(&module, source_map, output)?;
         }
         Commands::Init { name } => {
             init(name);
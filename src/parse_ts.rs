@@ -1,25 +1,77 @@
-use std::path::Path;
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 use swc_common::{
     self,
     errors::{ColorConfig, Handler},
     sync::Lrc,
-    SourceMap,
+    SourceFile, SourceMap, Spanned,
+};
+use swc_ecma_ast::{
+    Decl, ImportDecl, ImportSpecifier, Module, ModuleDecl, ModuleExportName, ModuleItem,
 };
-use swc_ecma_ast::Module;
 use swc_ecma_parser::{lexer::Lexer, Capturing, Parser, StringInput, Syntax};
 
-pub fn parse_ts(input_file_name: &String) -> Module {
+use crate::diagnostics::{Diagnostic, Diagnostics};
+
+/// Parses the program at `input_file_name` along with the files it imports relatively,
+/// which are merged into one module, each file after the ones it imports.
+pub fn parse_ts(input_file_name: &str) -> Result<(Module, Lrc<SourceMap>)> {
+    let (module, diagnostics) = load(input_file_name)?;
+    diagnostics.emit()?;
+    Ok((module, diagnostics.source_map().clone()))
+}
+
+/// Loads the program at `input_file_name` and its imports, collecting what's wrong with
+/// them instead of reporting it.
+fn load(input_file_name: &str) -> Result<(Module, Diagnostics)> {
     let cm: Lrc<SourceMap> = Default::default();
-    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+    let path = fs::canonicalize(input_file_name)
+        .with_context(|| format!("failed to load {}", input_file_name))?;
+    let entry = parse_file(&cm, &path)?;
+    let span = entry.span;
+    let mut loader = Loader {
+        root: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        diagnostics: Diagnostics::new(cm),
+        stack: vec![],
+        exports: HashMap::new(),
+        body: vec![],
+    };
+    loader.load(path, entry)?;
+    let module = Module {
+        span,
+        body: loader.body,
+        shebang: None,
+    };
+    Ok((module, loader.diagnostics))
+}
 
+/// Parses a single program given as source text, which can't import other files.
+#[cfg(test)]
+pub fn parse_source(source: &str) -> Result<(Module, Lrc<SourceMap>)> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(swc_common::FileName::Anon, source.to_string());
+    let module = parse_source_file(&cm, &fm, "<source>")?;
+    Ok((module, cm))
+}
+
+fn parse_file(cm: &Lrc<SourceMap>, path: &Path) -> Result<Module> {
     let fm = cm
-        .load_file(Path::new(&input_file_name))
-        .expect(&format!("failed to load {}", input_file_name));
+        .load_file(path)
+        .with_context(|| format!("failed to load {}", path.display()))?;
+    parse_source_file(cm, &fm, &path.display().to_string())
+}
+
+fn parse_source_file(cm: &Lrc<SourceMap>, fm: &SourceFile, name: &str) -> Result<Module> {
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
 
     let lexer = Lexer::new(
         Syntax::Typescript(Default::default()),
         Default::default(),
-        StringInput::from(&*fm),
+        StringInput::from(fm),
         None,
     );
 
@@ -27,14 +79,317 @@ pub fn parse_ts(input_file_name: &String) -> Module {
 
     let mut parser = Parser::new_from(capturing);
 
-    for e in parser.take_errors() {
+    let module = parser.parse_typescript_module().map_err(|e| {
         e.into_diagnostic(&handler).emit();
+        anyhow!("failed to parse {}", name)
+    })?;
+
+    let errors = parser.take_errors();
+    if !errors.is_empty() {
+        for e in errors {
+            e.into_diagnostic(&handler).emit();
+        }
+        return Err(anyhow!("failed to parse {}", name));
+    }
+
+    Ok(module)
+}
+
+struct Loader {
+    /// The directory of the program file, which paths are shown relative to.
+    root: PathBuf,
+    diagnostics: Diagnostics,
+    /// The files being loaded, each one imported by the one before it.
+    stack: Vec<PathBuf>,
+    /// The names each loaded file exports.
+    exports: HashMap<PathBuf, Vec<String>>,
+    body: Vec<ModuleItem>,
+}
+
+impl Loader {
+    fn load(&mut self, path: PathBuf, module: Module) -> Result<()> {
+        self.exports.insert(path.clone(), exported_names(&module));
+        self.stack.push(path.clone());
+        let is_entry = self.stack.len() == 1;
+        let mut body = vec![];
+        for item in module.body {
+            match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                    if import.src.value.ends_with(".json") =>
+                {
+                    if let Some(import) = self.idl_import(&path, import) {
+                        body.push(ModuleItem::ModuleDecl(ModuleDecl::Import(import)));
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                    if import.src.value.starts_with('.') =>
+                {
+                    self.import(&path, &import)?
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(d)) if !is_entry => {
+                    self.diagnostics.push(
+                        Diagnostic::new(d.span, "only the program file can declare the program")
+                            .with_hint(
+                                "imported files may only declare accounts, errors and constants",
+                            ),
+                    )
+                }
+                item => body.push(item),
+            }
+        }
+        self.stack.pop();
+        self.body.extend(body);
+        Ok(())
+    }
+
+    fn import(&mut self, from: &Path, import: &ImportDecl) -> Result<()> {
+        let src = import.src.value.as_ref();
+        let Some(path) = resolve(from, src) else {
+            self.diagnostics.push(
+                Diagnostic::new(import.src.span, format!("cannot find module `{}`", src))
+                    .with_label("no such file")
+                    .with_hint("relative imports are resolved to `.ts` files, e.g. `./state` to `state.ts`"),
+            );
+            return Ok(());
+        };
+        if let Some(start) = self.stack.iter().position(|p| *p == path) {
+            let cycle: Vec<String> = self.stack[start..]
+                .iter()
+                .chain([&path])
+                .map(|p| self.display(p))
+                .collect();
+            self.diagnostics.push(
+                Diagnostic::new(
+                    import.src.span,
+                    format!("import cycle: {}", cycle.join(" -> ")),
+                )
+                .with_hint("move what both files need into a file of its own"),
+            );
+            return Ok(());
+        }
+        // Files imported more than once are only merged the first time
+        if !self.exports.contains_key(&path) {
+            let module = parse_file(self.diagnostics.source_map(), &path)?;
+            self.load(path.clone(), module)?;
+        }
+        let exports = &self.exports[&path];
+        for specifier in import.specifiers.iter() {
+            let named = match specifier {
+                ImportSpecifier::Named(named) => named,
+                _ => {
+                    self.diagnostics.push(
+                        Diagnostic::new(specifier.span(), "only named imports are supported")
+                            .with_hint(format!(
+                            "import what you need by name, e.g. `import {{ Vault }} from \"{}\"`",
+                            src
+                        )),
+                    );
+                    continue;
+                }
+            };
+            let name = match &named.imported {
+                Some(ModuleExportName::Ident(i)) => i.sym.to_string(),
+                Some(ModuleExportName::Str(s)) => s.value.to_string(),
+                None => named.local.sym.to_string(),
+            };
+            if named.local.sym.as_ref() != name {
+                self.diagnostics.push(
+                    Diagnostic::new(named.span, "imports can't be renamed")
+                        .with_hint(format!("refer to it as `{}`", name)),
+                );
+            } else if !exports.contains(&name) {
+                self.diagnostics.push(Diagnostic::new(
+                    named.span,
+                    format!("`{}` is not exported by `{}`", name, src),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks an `import Lending from "./lending.json"` of an Anchor IDL, which is kept for
+    /// `lower` to read with its path made absolute.
+    fn idl_import(&mut self, from: &Path, mut import: ImportDecl) -> Option<ImportDecl> {
+        let src = import.src.value.to_string();
+        let path = from
+            .parent()
+            .map(|dir| dir.join(&src))
+            .filter(|p| p.is_file())
+            .and_then(|p| fs::canonicalize(p).ok());
+        let Some(path) = path else {
+            self.diagnostics.push(
+                Diagnostic::new(import.src.span, format!("cannot find module `{}`", src))
+                    .with_label("no such file")
+                    .with_hint("IDLs are resolved relative to the file importing them"),
+            );
+            return None;
+        };
+        if !matches!(import.specifiers.as_slice(), [ImportSpecifier::Default(_)]) {
+            self.diagnostics.push(
+                Diagnostic::new(import.span, "an IDL is imported as a whole").with_hint(format!(
+                    "use a default import, e.g. `import Lending from \"{}\"`",
+                    src
+                )),
+            );
+            return None;
+        }
+        import.src.value = path.to_string_lossy().as_ref().into();
+        Some(import)
+    }
+
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Finds the file a relative import of `src` from `from` refers to.
+fn resolve(from: &Path, src: &str) -> Option<PathBuf> {
+    let base = from.parent()?.join(src);
+    let candidates = match base.extension() {
+        Some(ext) if ext == "ts" => vec![base],
+        _ => vec![
+            PathBuf::from(format!("{}.ts", base.display())),
+            base.join("index.ts"),
+        ],
+    };
+    candidates
+        .into_iter()
+        .find(|p| p.is_file())
+        .and_then(|p| fs::canonicalize(p).ok())
+}
+
+fn exported_names(module: &Module) -> Vec<String> {
+    let mut names = vec![];
+    for item in module.body.iter() {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) => &e.decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::Class(c) => names.push(c.ident.sym.to_string()),
+            Decl::TsInterface(i) => names.push(i.id.sym.to_string()),
+            Decl::TsEnum(e) => names.push(e.id.sym.to_string()),
+            Decl::TsTypeAlias(t) => names.push(t.id.sym.to_string()),
+            Decl::Fn(f) => names.push(f.ident.sym.to_string()),
+            Decl::Var(v) => names.extend(
+                v.decls
+                    .iter()
+                    .filter_map(|d| Some(d.name.as_ident()?.sym.to_string())),
+            ),
+            _ => {}
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use swc_ecma_ast::Module;
+
+    use super::load;
+
+    const STATE: &str = "export interface Vault extends Account { amount: u64 }";
+
+    /// Writes `files` into a directory of their own and loads the `program.ts` among them,
+    /// along with the messages of what's wrong with them.
+    fn load_files(test: &str, files: &[(&str, &str)]) -> (Module, Vec<String>) {
+        let dir = std::env::temp_dir().join(format!("poseidon-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (name, source) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        let loaded = load(dir.join("program.ts").to_str().unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+        let (module, diagnostics) = loaded.unwrap();
+        (module, diagnostics.messages())
     }
 
-    let module = parser
-        .parse_typescript_module()
-        .map_err(|e| e.into_diagnostic(&handler).emit())
-        .expect("Failed to parse module.");
+    #[test]
+    fn import_cycles_are_reported() {
+        let (_, messages) = load_files(
+            "cycle",
+            &[
+                ("program.ts", "import { A } from \"./a\";"),
+                ("a.ts", "import { B } from \"./b\";\nexport const A = 1;"),
+                ("b.ts", "import { A } from \"./a\";\nexport const B = 2;"),
+            ],
+        );
+        assert_eq!(messages, ["import cycle: a.ts -> b.ts -> a.ts"]);
+    }
+
+    #[test]
+    fn missing_modules_are_reported() {
+        let (_, messages) = load_files(
+            "missing",
+            &[("program.ts", "import { Vault } from \"./state\";")],
+        );
+        assert_eq!(messages, ["cannot find module `./state`"]);
+    }
 
-    return module;
+    /// Loads a program file made of `import` next to a file exporting `Vault`.
+    fn load_import(test: &str, import: &str) -> Vec<String> {
+        load_files(test, &[("program.ts", import), ("state.ts", STATE)]).1
+    }
+
+    #[test]
+    fn imports_cannot_be_renamed() {
+        let messages = load_import("renamed", "import { Vault as Safe } from \"./state\";");
+        assert_eq!(messages, ["imports can't be renamed"]);
+    }
+
+    #[test]
+    fn default_imports_are_rejected() {
+        let messages = load_import("default-import", "import State from \"./state\";");
+        assert_eq!(messages, ["only named imports are supported"]);
+    }
+
+    #[test]
+    fn imported_names_have_to_be_exported() {
+        let messages = load_import("not-exported", "import { Pool } from \"./state\";");
+        assert_eq!(messages, ["`Pool` is not exported by `./state`"]);
+    }
+
+    #[test]
+    fn only_the_program_file_exports_a_default() {
+        let (_, messages) = load_files(
+            "default",
+            &[
+                ("program.ts", "import { Vault } from \"./state\";"),
+                (
+                    "state.ts",
+                    "export default class Other {}\nexport interface Vault extends Account {}",
+                ),
+            ],
+        );
+        assert_eq!(messages, ["only the program file can declare the program"]);
+    }
+
+    #[test]
+    fn directories_are_imported_from_their_index() {
+        let (module, messages) = load_files(
+            "index",
+            &[
+                ("program.ts", "import { Vault } from \"./state\";"),
+                ("state/index.ts", STATE),
+            ],
+        );
+        assert!(messages.is_empty(), "{:?}", messages);
+        // The imported file is merged in place of the import
+        assert_eq!(module.body.len(), 1);
+    }
+
+    #[test]
+    fn idls_are_default_imports() {
+        let input = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/idls/named_import.ts");
+        let (_, diagnostics) = load(input.to_str().unwrap()).unwrap();
+        assert_eq!(diagnostics.messages(), ["an IDL is imported as a whole"]);
+    }
 }
@@ -1,67 +1,225 @@
-use core::panic;
-use rust_format::{Formatter, PrettyPlease, RustFmt};
-use std::{collections::HashMap, fs};
+use std::{fs, path::Path};
+use swc_common::{sync::Lrc, SourceMap};
+use swc_ecma_ast::Module;
 
-use crate::rs_types::{ProgramAccount, ProgramModule};
+use crate::{
+    codegen::{anchor, client, format::format, idl},
+    diagnostics::Diagnostics,
+    ir::ProgramModule,
+};
 use anyhow::Result;
-use swc_ecma_ast::*;
-
-pub fn transpile(module: &Module, output_file_name: &String) -> Result<()> {
-    let mut imports = vec![];
-    let mut accounts: HashMap<String, ProgramAccount> = HashMap::new();
-    let mut program_class: Option<ClassExpr> = None;
-    let mut custom_types: HashMap<String, ProgramAccount> = HashMap::new();
-    let mut program = ProgramModule::new();
-    let mut stack: Vec<&ModuleItem> = module.body.iter().collect();
-
-    while let Some(item) = stack.pop() {
-        match item {
-            // Extract imports
-            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
-                let src = import_decl.src.value.to_string();
-                let mut names = Vec::new();
-                for specifier in &import_decl.specifiers {
-                    if let ImportSpecifier::Named(named_specifier) = specifier {
-                        names.push(named_specifier.local.sym.to_string());
-                    }
-                }
-                imports.push((src, names));
-            }
-            // Extract program class
-            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(default_export_decl)) => {
-                program_class = match default_export_decl.clone().decl.class() {
-                    Some(p) => Some(p),
-                    None => panic!("Default export must be a Class"),
-                };
-            }
-            // Extract custom accounts
-            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(class_decl)) => {
-                match class_decl.clone().decl {
-                    Decl::TsInterface(interface) => {
-                        let custom_account = ProgramAccount::from_ts_expr(*interface);
-                        custom_types.insert(custom_account.name.clone(), custom_account.clone());
-                        accounts.insert(custom_account.name.clone(), custom_account.clone());
-                    }
-                    _ => panic!("Invalid export statement"),
-                }
-            }
-            _ => panic!("Invalid syntax, cannot match: {:?}", item),
-        }
+
+pub fn transpile(
+    module: &Module,
+    source_map: Lrc<SourceMap>,
+    output_file_name: &str,
+    idl_file_name: Option<&str>,
+) -> Result<()> {
+    let program = lower(module, source_map)?;
+    fs::write(output_file_name, render(&program)?)?;
+    if let Some(idl_file_name) = idl_file_name {
+        write_idl(&program, idl_file_name)?;
     }
+    Ok(())
+}
 
-    program.accounts = accounts.into_values().collect();
-    program.custom_types.clone_from(&custom_types);
-    // print!("{:#?}", program_class);
-    match program_class {
-        Some(c) => {
-            program.populate_from_class_expr(&c, &custom_types)?;
+/// Transpiles into the standard Anchor crate layout, writing its files into `src_dir`.
+/// Returns the program, for the caller to set up the rest of the crate.
+pub fn transpile_crate(
+    module: &Module,
+    source_map: Lrc<SourceMap>,
+    src_dir: &Path,
+    idl_file_name: Option<&str>,
+) -> Result<ProgramModule> {
+    let program = lower(module, source_map)?;
+    for (path, tokens) in anchor::generate_crate(&program)? {
+        let path = src_dir.join(path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
         }
-        None => panic!("Program class undefined"),
+        fs::write(path, format(tokens)?)?;
+    }
+    if let Some(idl_file_name) = idl_file_name {
+        write_idl(&program, idl_file_name)?;
     }
-    let serialized_program = program.to_tokens();
-    fs::write(
-        &output_file_name,
-        PrettyPlease::default().format_str(serialized_program?.to_string())?,
-    )?;
+    Ok(program)
+}
+
+/// Writes the IDL of the program without transpiling it.
+pub fn generate_idl(
+    module: &Module,
+    source_map: Lrc<SourceMap>,
+    output_file_name: &str,
+) -> Result<()> {
+    let program = lower(module, source_map)?;
+    write_idl(&program, output_file_name)
+}
+
+/// Writes a TypeScript client of the program without transpiling it.
+pub fn generate_client(
+    module: &Module,
+    source_map: Lrc<SourceMap>,
+    output_file_name: &str,
+) -> Result<()> {
+    let program = lower(module, source_map)?;
+    fs::write(output_file_name, client::generate(&program)?)?;
     Ok(())
 }
+
+fn lower(module: &Module, source_map: Lrc<SourceMap>) -> Result<ProgramModule> {
+    let mut diagnostics = Diagnostics::new(source_map);
+    let program = ProgramModule::from_module(module, &mut diagnostics);
+    diagnostics.emit()?;
+    Ok(program)
+}
+
+/// Generates the program as a single formatted Rust file.
+fn render(program: &ProgramModule) -> Result<String> {
+    format(anchor::generate(program)?)
+}
+
+fn write_idl(program: &ProgramModule, output_file_name: &str) -> Result<()> {
+    let idl = idl::generate(program)?;
+    fs::write(output_file_name, serde_json::to_string_pretty(&idl)? + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::parse_ts::{parse_source, parse_ts};
+
+    /// Wraps `methods` in a program class, followed by the module level `decls`.
+    pub fn program(methods: &str, decls: &str) -> String {
+        format!(
+            "import {{ Account, Pubkey, Result, Signer }} from \"@solanaturbine/poseidon\";\n\
+             export default class TestProgram {{\n\
+             static PROGRAM_ID = new Pubkey(\"11111111111111111111111111111111\");\n\
+             {}\n}}\n{}",
+            methods, decls
+        )
+    }
+
+    /// Lowers a program given as source text, failing with the messages of its errors.
+    pub fn lower_source(source: &str) -> Result<ProgramModule, Vec<String>> {
+        let (module, source_map) = parse_source(source).map_err(|e| vec![e.to_string()])?;
+        let mut diagnostics = Diagnostics::new(source_map);
+        let program = ProgramModule::from_module(&module, &mut diagnostics);
+        match diagnostics.has_errors() {
+            true => Err(diagnostics.messages()),
+            false => Ok(program),
+        }
+    }
+
+    /// Transpiles a program given as source text into a single Rust file.
+    pub fn transpile_source(source: &str) -> String {
+        let program = lower_source(source).unwrap_or_else(|e| panic!("{:#?}", e));
+        render(&program).unwrap()
+    }
+
+    /// Checks the program and client generated from an example against the reference
+    /// outputs under `examples/<name>/rust` and `examples/<name>/client`, which
+    /// `POSEIDON_BLESS=1 cargo test` rewrites instead.
+    fn check_example(name: &str) {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("examples")
+            .join(name);
+        let input = dir.join("typescript").join(format!("{}.ts", name));
+        let (module, source_map) = parse_ts(input.to_str().unwrap()).unwrap();
+        let program = lower(&module, source_map).unwrap();
+        check_output(
+            &dir.join("rust").join(format!("{}.rs", name)),
+            &render(&program).unwrap(),
+        );
+        check_output(
+            &dir.join("client").join(format!("{}.ts", name)),
+            &client::generate(&program).unwrap(),
+        );
+    }
+
+    fn check_output(reference: &Path, actual: &str) {
+        if std::env::var_os("POSEIDON_BLESS").is_some() {
+            fs::create_dir_all(reference.parent().unwrap()).unwrap();
+            fs::write(reference, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(reference).unwrap();
+        assert!(
+            actual == expected,
+            "{} differs, rerun with POSEIDON_BLESS=1 if that is intended:\n{}",
+            reference.display(),
+            actual
+        );
+    }
+
+    /// Everything generated from `program`: the Rust program as one file and split into a
+    /// crate, its IDL and its client.
+    fn outputs(program: &ProgramModule) -> Vec<String> {
+        let mut outputs = vec![
+            render(program).unwrap(),
+            serde_json::to_string_pretty(&idl::generate(program).unwrap()).unwrap(),
+            client::generate(program).unwrap(),
+        ];
+        for (path, tokens) in anchor::generate_crate(program).unwrap() {
+            outputs.push(format!("{}\n{}", path, tokens));
+        }
+        outputs
+    }
+
+    #[test]
+    fn output_is_deterministic() {
+        for name in ["escrow", "favorites", "vault", "vote"] {
+            let input = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("examples")
+                .join(name)
+                .join("typescript")
+                .join(format!("{}.ts", name));
+            let transpile = || {
+                let (module, source_map) = parse_ts(input.to_str().unwrap()).unwrap();
+                outputs(&lower(&module, source_map).unwrap())
+            };
+            assert_eq!(transpile(), transpile(), "{} differs between runs", name);
+        }
+    }
+
+    #[test]
+    fn imported_idls_are_invoked_through_their_crates() {
+        let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/idls/caller.ts");
+        let (module, source_map) = parse_ts(input.to_str().unwrap()).unwrap();
+        let rust = render(&lower(&module, source_map).unwrap()).unwrap();
+        // A foreign Anchor program names its accounts structs after the instructions
+        assert!(
+            rust.contains("pub lending_program: Program<'info, lending::program::Lending>,"),
+            "{}",
+            rust
+        );
+        assert!(rust.contains("let cpi_accounts = lending::cpi::accounts::Deposit {"));
+        assert!(rust.contains("lending::cpi::deposit(cpi_ctx, amount, memo.to_string())?;"));
+        // One built with Poseidon names them `<Instruction>Context`
+        assert!(rust.contains("counter_program::program::CounterProgram"));
+        assert!(
+            rust.contains("let cpi_accounts = counter_program::cpi::accounts::IncrementContext {")
+        );
+        assert!(rust.contains("counter_program::cpi::increment(cpi_ctx, amount)?;"));
+    }
+
+    #[test]
+    fn escrow_example() {
+        check_example("escrow");
+    }
+
+    #[test]
+    fn favorites_example() {
+        check_example("favorites");
+    }
+
+    #[test]
+    fn vault_example() {
+        check_example("vault");
+    }
+
+    #[test]
+    fn vote_example() {
+        check_example("vote");
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{Error, Result};
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 
 pub const STANDARD_TYPES: [&str; 16] = [
@@ -21,66 +21,166 @@ pub const STANDARD_TYPES: [&str; 16] = [
     "Pubkey",
 ];
 
-pub const STANDARD_ARRAY_TYPES: [&str; 13] = [
-    "Vec<Str>",
-    "Vec<u8>",
-    "Vec<i8>",
-    "Vec<u16>",
-    "Vec<i16>",
-    "Vec<u32>",
-    "Vec<i32>",
-    "Vec<u64>",
-    "Vec<i64>",
-    "Vec<u128>",
-    "Vec<i128>",
-    "Vec<Pubkey>",
-    "Vec<Boolean>",
-];
+use crate::errors::PoseidonError;
 
-pub const STANDARD_ACCOUNT_TYPES: [&str; 7] = [
-    "Signer",
-    "UncheckedAccount",
-    "AccountInfo",
-    "TokenAccount",
-    "SystemAccount",
-    "AssociatedTokenAccount",
-    "Mint",
-];
+/// Splits a generic type like `Array<Vec<u8>, 4>` into its name and its top level
+/// parameters, or returns `None` for a type without any.
+pub fn generic_params(of_type: &str) -> Option<(&str, Vec<&str>)> {
+    let (name, rest) = of_type.split_once('<')?;
+    let inner = rest.strip_suffix('>')?;
+    let mut params = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(inner[start..].trim());
+    Some((name, params))
+}
 
-use crate::errors::PoseidonError;
+/// Whether `of_type` names a type the program declares itself, like a struct.
+fn is_defined_type(of_type: &str) -> bool {
+    of_type.starts_with(|c: char| c.is_ascii_uppercase())
+        && of_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !STANDARD_TYPES.contains(&of_type)
+}
+
+/// The shape of a Poseidon type, read off the canonical name the `ir` keeps for it,
+/// like `Vec<Array<u8, 32>>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoseidonType<'a> {
+    /// An integer, `Boolean` or `Pubkey`.
+    Scalar(&'a str),
+    Str,
+    /// `Uint8Array`, a length-prefixed byte string like `Vec<u8>`.
+    Bytes,
+    Vec(Box<PoseidonType<'a>>),
+    Array(Box<PoseidonType<'a>>, u32),
+    Option(Box<PoseidonType<'a>>),
+    /// A struct or enum of the program.
+    Defined(&'a str),
+}
+
+impl<'a> PoseidonType<'a> {
+    pub fn parse(of_type: &'a str) -> Result<Self, PoseidonError> {
+        let invalid = || PoseidonError::InvalidType(of_type.to_string());
+        if let Some((name, params)) = generic_params(of_type) {
+            return match (name, params.as_slice()) {
+                ("Vec", [item]) => Ok(Self::Vec(Box::new(Self::parse(item)?))),
+                ("Option", [item]) => Ok(Self::Option(Box::new(Self::parse(item)?))),
+                ("Array", [item, len]) => Ok(Self::Array(
+                    Box::new(Self::parse(item)?),
+                    len.parse().map_err(|_| invalid())?,
+                )),
+                _ => Err(invalid()),
+            };
+        }
+        match of_type {
+            "Str" => Ok(Self::Str),
+            "Uint8Array" => Ok(Self::Bytes),
+            _ if STANDARD_TYPES.contains(&of_type) => Ok(Self::Scalar(of_type)),
+            _ if is_defined_type(of_type) => Ok(Self::Defined(of_type)),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn rs_type(&self) -> TokenStream {
+        match self {
+            PoseidonType::Scalar("Boolean") => quote! { bool },
+            PoseidonType::Str => quote! { String },
+            PoseidonType::Bytes => quote! { Vec<u8> },
+            PoseidonType::Vec(item) => {
+                let item = item.rs_type();
+                quote! { Vec<#item> }
+            }
+            PoseidonType::Array(item, len) => {
+                let item = item.rs_type();
+                let len = Literal::u32_unsuffixed(*len);
+                quote! { [#item; #len] }
+            }
+            PoseidonType::Option(item) => {
+                let item = item.rs_type();
+                quote! { Option<#item> }
+            }
+            // Structs and enums of the program, which lowering has already checked
+            PoseidonType::Scalar(name) | PoseidonType::Defined(name) => {
+                let ident = Ident::new(name, Span::call_site());
+                quote! { #ident }
+            }
+        }
+    }
+
+    /// The size of a scalar once serialized with Borsh, which writes `usize` and `isize`
+    /// as 64 bit integers.
+    pub fn scalar_size(name: &str) -> u32 {
+        match name {
+            "u8" | "i8" | "Boolean" => 1,
+            "u16" | "i16" => 2,
+            "u32" | "i32" => 4,
+            "u64" | "i64" | "usize" | "isize" => 8,
+            "u128" | "i128" => 16,
+            "Pubkey" => 32,
+            _ => 0,
+        }
+    }
+}
 
 pub fn rs_type_from_str(str: &str) -> Result<TokenStream, Error> {
-    match str {
-        "Str" => Ok(quote! { String }),
-        "Vec<Str>" => Ok(quote! { Vec<String> }),
-        "Vec<u8>" => Ok(quote! { Vec<u8> }),
-        "Vec<i8>" => Ok(quote! { Vec<i8> }),
-        "Vec<u16>" => Ok(quote! { Vec<u16> }),
-        "Vec<i16>" => Ok(quote! { Vec<i16> }),
-        "Vec<u32>" => Ok(quote! { Vec<u32> }),
-        "Vec<i32>" => Ok(quote! { Vec<i32> }),
-        "Vec<u64>" => Ok(quote! { Vec<u64> }),
-        "Vec<i64>" => Ok(quote! { Vec<i64> }),
-        "Vec<u128>" => Ok(quote! { Vec<u128> }),
-        "Vec<i128>" => Ok(quote! { Vec<i128> }),
-        "Vec<Pubkey>" => Ok(quote! { Vec<Pubkey> }),
-        "Vec<Boolean>" => Ok(quote! { Vec<bool> }),
-        "u8" => Ok(quote! { u8 }),
-        "i8" => Ok(quote! { i8 }),
-        "u16" => Ok(quote! { u16 }),
-        "i16" => Ok(quote! { i16 }),
-        "u32" => Ok(quote! { u32 }),
-        "i32" => Ok(quote! { i32 }),
-        "u64" => Ok(quote! { u64 }),
-        "i64" => Ok(quote! { i64 }),
-        "u128" => Ok(quote! { u128 }),
-        "i128" => Ok(quote! { i128 }),
-        "usize" => Ok(quote! { usize }),
-        "isize" => Ok(quote! { isize }),
-        "Boolean" => Ok(quote! { bool }),
-        "Pubkey" => Ok(quote! { Pubkey }),
-        "Uint8Array" => Ok(quote! { Vec<u8> }),
-        // "Signer" => Ok(quote!{Signer}),
-        _ => Err(PoseidonError::InvalidType(str.to_string()))?,
+    Ok(PoseidonType::parse(str)?.rs_type())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_sizes() {
+        let sizes = [
+            ("u8", 1),
+            ("i8", 1),
+            ("Boolean", 1),
+            ("u16", 2),
+            ("i16", 2),
+            ("u32", 4),
+            ("i32", 4),
+            ("u64", 8),
+            ("i64", 8),
+            ("usize", 8),
+            ("isize", 8),
+            ("u128", 16),
+            ("i128", 16),
+            ("Pubkey", 32),
+        ];
+        for (name, size) in sizes {
+            assert_eq!(
+                PoseidonType::parse(name).unwrap(),
+                PoseidonType::Scalar(name)
+            );
+            assert_eq!(PoseidonType::scalar_size(name), size, "{}", name);
+        }
+    }
+
+    #[test]
+    fn parses_nested_types() {
+        use PoseidonType::*;
+        assert_eq!(
+            PoseidonType::parse("Vec<Option<Array<u8, 32>>>").unwrap(),
+            Vec(Box::new(Option(Box::new(Array(
+                Box::new(Scalar("u8")),
+                32
+            )))))
+        );
+        assert_eq!(PoseidonType::parse("Order").unwrap(), Defined("Order"));
+        assert!(PoseidonType::parse("Array<u8>").is_err());
+        assert!(PoseidonType::parse("number").is_err());
     }
 }
@@ -0,0 +1,15 @@
+import { Pubkey, Result, Signer, Str, SystemAccount, UncheckedAccount, u64 } from "@solanaturbine/poseidon";
+import Lending from "./lending.json";
+import CounterProgram from "./counter.json";
+
+export default class CallerProgram {
+  static PROGRAM_ID = new Pubkey("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+  deposit(owner: Signer, pool: UncheckedAccount, vault: SystemAccount, amount: u64, memo: Str<16>): Result {
+    Lending.deposit({ user: owner, pool, vault }, { amount, memo });
+  }
+
+  increment(authority: Signer, counter: UncheckedAccount, amount: u64): Result {
+    CounterProgram.increment({ authority, counter }, { amount });
+  }
+}
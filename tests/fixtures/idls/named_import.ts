@@ -0,0 +1 @@
+import { Lending } from "./lending.json";